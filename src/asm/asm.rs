@@ -0,0 +1,513 @@
+use std::fmt;
+
+use crate::cpu::schema::{Instruction, Profile};
+
+use super::schema::{AsmError, Assembler, Statement, Symbol, ORIGIN};
+
+/// Mnémoniques d'instructions, pour reconnaître une étiquette sans `:`.
+const MNEMONICS: [&str; 35] = [
+    "CLS", "RET", "SYS", "JP", "CALL", "SE", "SNE", "LD", "ADD", "OR", "AND", "XOR", "SUB", "SHR",
+    "SUBN", "SHL", "RND", "DRW", "SKP", "SKNP", "SCD", "SCR", "SCL", "EXIT", "LOW", "HIGH", "SAVE",
+    "LOAD", "PLANE", "AUDIO", "PITCH", "SCU", "DB", "DW", "DA",
+];
+
+const DIRECTIVES: [&str; 15] = [
+    "=", "EQU", "DS", "OPTION", "ALIGN", "USED", "XREF", "END", "DEFINE", "UNDEF", "IFDEF",
+    "IFUND", "ELSE", "ENDIF", "ORG",
+];
+
+fn error(line: usize, message: impl Into<String>) -> AsmError {
+    AsmError {
+        line,
+        message: message.into(),
+    }
+}
+
+/// Retire le commentaire `;` en dehors des chaînes entre apostrophes.
+fn strip_comment(line: &str) -> &str {
+    let mut quoted = false;
+    for (i, c) in line.char_indices() {
+        match c {
+            '\'' => quoted = !quoted,
+            ';' if !quoted => return &line[..i],
+            _ => {}
+        }
+    }
+    line
+}
+
+/// Sépare les opérandes sur les virgules hors chaînes et parenthèses.
+fn split_operands(text: &str) -> Vec<String> {
+    let mut operands = Vec::new();
+    let (mut quoted, mut depth, mut start) = (false, 0, 0);
+    for (i, c) in text.char_indices() {
+        match c {
+            '\'' => quoted = !quoted,
+            '(' if !quoted => depth += 1,
+            ')' if !quoted => depth -= 1,
+            ',' if !quoted && depth == 0 => {
+                operands.push(text[start..i].trim().to_string());
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    operands.push(text[start..].trim().to_string());
+    // `DB A, B,` : une virgule finale ne produit pas d'octet
+    if operands.last().is_some_and(|o| o.is_empty()) {
+        operands.pop();
+    }
+    operands
+}
+
+fn is_keyword(word: &str) -> bool {
+    let upper = word.to_ascii_uppercase();
+    MNEMONICS.contains(&upper.as_str()) || DIRECTIVES.contains(&upper.as_str())
+}
+
+/// Découpe une ligne en étiquette, mnémonique et texte des opérandes.
+fn parse_line(code: &str) -> (Option<String>, Option<String>, &str) {
+    let mut rest = code.trim();
+    let mut label = None;
+
+    let first = rest.split_whitespace().next().unwrap_or("");
+    if let Some(pos) = first.find(':') {
+        label = Some(first[..pos].to_ascii_uppercase());
+        rest = rest[pos + 1..].trim_start();
+    } else if !first.is_empty() && !is_keyword(first) {
+        // `SCORE DW 0` ou `XSTART EQU 23` : étiquette sans deux-points
+        let second = rest[first.len()..].split_whitespace().next().unwrap_or("");
+        if is_keyword(second) {
+            label = Some(first.to_ascii_uppercase());
+            rest = rest[first.len()..].trim_start();
+        }
+    }
+
+    let mnemonic = rest.split_whitespace().next().map(str::to_ascii_uppercase);
+    let operands = match &mnemonic {
+        Some(m) => rest[m.len()..].trim(),
+        None => "",
+    };
+    (label, mnemonic, operands)
+}
+
+fn register(operand: &str) -> Option<u8> {
+    let upper = operand.to_ascii_uppercase();
+    let digit = upper.strip_prefix('V')?;
+    if digit.len() != 1 {
+        return None;
+    }
+    u8::from_str_radix(digit, 16).ok()
+}
+
+impl Default for Assembler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Assembler {
+    pub fn new() -> Self {
+        Assembler {
+            symbols: Default::default(),
+            defines: Default::default(),
+            statements: Vec::new(),
+            profile: Profile::Modern,
+            align: true,
+            addr: ORIGIN,
+        }
+    }
+
+    /// Équivalent d'un `DEFINE name` en tête de source, pour les IFDEF.
+    pub fn define(&mut self, name: &str) {
+        self.defines.insert(name.to_ascii_uppercase());
+    }
+
+    /// Assemble le source complet et retourne le binaire chargé en 0x200.
+    pub fn assemble(mut self, source: &str) -> Result<Vec<u8>, AsmError> {
+        self.first_pass(source)?;
+
+        let mut out = Vec::new();
+        for statement in &self.statements {
+            out.resize((statement.addr - ORIGIN) as usize, 0);
+            self.encode(statement, &mut out)?;
+        }
+        Ok(out)
+    }
+
+    /// Première passe : conditions, symboles et adresse de chaque instruction.
+    fn first_pass(&mut self, source: &str) -> Result<(), AsmError> {
+        let mut conditions: Vec<(bool, bool)> = Vec::new(); // (actif, parent actif)
+
+        for (index, raw) in source.lines().enumerate() {
+            let line = index + 1;
+            let (label, mnemonic, operands) = parse_line(strip_comment(raw));
+            let active = conditions.last().is_none_or(|&(on, _)| on);
+
+            match mnemonic.as_deref() {
+                Some(m @ ("IFDEF" | "IFUND")) => {
+                    let defined = self.defines.contains(&operands.to_ascii_uppercase());
+                    conditions.push((active && defined == (m == "IFDEF"), active));
+                }
+                Some("ELSE") => {
+                    let (on, parent) = conditions
+                        .pop()
+                        .ok_or_else(|| error(line, "ELSE without IFDEF"))?;
+                    conditions.push((parent && !on, parent));
+                }
+                Some("ENDIF") => {
+                    conditions
+                        .pop()
+                        .ok_or_else(|| error(line, "ENDIF without IFDEF"))?;
+                }
+                _ => {}
+            }
+            if !active {
+                continue;
+            }
+
+            let is_equ = matches!(mnemonic.as_deref(), Some("=" | "EQU"));
+            // `?` désigne l'adresse courante, figée dès la première passe
+            let here = format!("#{:X}", self.aligned(mnemonic.as_deref()));
+            let operands = match mnemonic.as_deref() {
+                Some("DA") => operands.to_string(),
+                _ => operands.replace('?', &here),
+            };
+            let operands = operands.as_str();
+            if let Some(name) = label {
+                if self.symbols.contains_key(&name) {
+                    return Err(error(line, format!("symbol '{}' is already defined", name)));
+                }
+                let symbol = if is_equ {
+                    Symbol::Expr {
+                        text: operands.to_string(),
+                        line,
+                    }
+                } else {
+                    // une étiquette seule sur sa ligne suit l'alignement de ce qui vient
+                    Symbol::Value(self.aligned(mnemonic.as_deref()) as i64)
+                };
+                self.symbols.insert(name, symbol);
+            }
+
+            let Some(mnemonic) = mnemonic else {
+                continue;
+            };
+            match mnemonic.as_str() {
+                "=" | "EQU" if !is_equ || operands.is_empty() => {
+                    return Err(error(line, "EQU needs a name and a value"));
+                }
+                "IFDEF" | "IFUND" | "ELSE" | "ENDIF" | "=" | "EQU" | "USED" | "XREF" => {}
+                "END" => break,
+                "DEFINE" => {
+                    self.define(operands);
+                }
+                "UNDEF" => {
+                    self.defines.remove(&operands.to_ascii_uppercase());
+                }
+                "ALIGN" => {
+                    self.align = match operands.to_ascii_uppercase().as_str() {
+                        "ON" => true,
+                        "OFF" => false,
+                        _ => return Err(error(line, "ALIGN expects ON or OFF")),
+                    };
+                }
+                "OPTION" => self.option(operands, line)?,
+                "ORG" => {
+                    let addr = self.address(operands, line)?;
+                    if addr < self.addr {
+                        return Err(error(line, "ORG cannot move backwards"));
+                    }
+                    self.addr = addr;
+                }
+                "DS" => {
+                    let size = self.address(operands, line)?;
+                    self.addr = self
+                        .addr
+                        .checked_add(size)
+                        .ok_or_else(|| error(line, "DS goes past the end of memory"))?;
+                }
+                _ => self.push_statement(mnemonic, operands, line)?,
+            }
+        }
+
+        if !conditions.is_empty() {
+            return Err(error(source.lines().count(), "missing ENDIF"));
+        }
+        Ok(())
+    }
+
+    /// Adresse où sera placé `mnemonic` une fois l'alignement appliqué.
+    fn aligned(&self, mnemonic: Option<&str>) -> u16 {
+        let needs_align = match mnemonic {
+            Some("DB" | "DA" | "DS") => false,
+            Some(m) if DIRECTIVES.contains(&m) => false,
+            _ => true,
+        };
+        if self.align && needs_align && self.addr % 2 == 1 {
+            self.addr + 1
+        } else {
+            self.addr
+        }
+    }
+
+    fn push_statement(
+        &mut self,
+        mnemonic: String,
+        operands: &str,
+        line: usize,
+    ) -> Result<(), AsmError> {
+        self.addr = self.aligned(Some(&mnemonic));
+        let operands = if mnemonic == "DA" {
+            vec![operands.to_string()]
+        } else {
+            split_operands(operands)
+        };
+
+        let size = match mnemonic.as_str() {
+            "DB" => operands.len(),
+            "DW" => operands.len() * 2,
+            "DA" => Self::string(&operands[0], line)?.len(),
+            m if MNEMONICS.contains(&m) => 2,
+            _ => return Err(error(line, format!("unknown instruction '{}'", mnemonic))),
+        };
+
+        self.statements.push(Statement {
+            line,
+            addr: self.addr,
+            mnemonic,
+            operands,
+            profile: self.profile,
+        });
+        self.addr = self
+            .addr
+            .checked_add(size as u16)
+            .ok_or_else(|| error(line, "program goes past the end of memory"))?;
+        Ok(())
+    }
+
+    fn option(&mut self, operands: &str, line: usize) -> Result<(), AsmError> {
+        self.profile = match operands.to_ascii_uppercase().as_str() {
+            "BINARY" => return Ok(()),
+            "CHIP8" => Profile::CosmacVip,
+            "CHIP48" => Profile::Chip48,
+            "SCHIP10" | "SCHIP11" => Profile::SuperChip,
+            "XOCHIP" => Profile::XoChip,
+            "HPASC" | "HPBIN" | "STRING" => {
+                return Err(error(line, "only OPTION BINARY output is supported"))
+            }
+            other => return Err(error(line, format!("unknown option '{}'", other))),
+        };
+        Ok(())
+    }
+
+    /// Chaîne `DA 'texte'`, apostrophe doublée pour l'échapper.
+    fn string(operand: &str, line: usize) -> Result<Vec<u8>, AsmError> {
+        let inner = operand
+            .strip_prefix('\'')
+            .and_then(|s| s.strip_suffix('\''))
+            .ok_or_else(|| error(line, "DA expects a quoted string"))?;
+        Ok(inner.replace("''", "'").into_bytes())
+    }
+
+    fn value(&self, operand: &str, max: i64, line: usize) -> Result<i64, AsmError> {
+        let value = self.eval(operand, line)?;
+        if !(0..=max).contains(&value) {
+            return Err(error(
+                line,
+                format!(
+                    "value {} of '{}' is out of range 0..={}",
+                    value, operand, max
+                ),
+            ));
+        }
+        Ok(value)
+    }
+
+    fn address(&self, operand: &str, line: usize) -> Result<u16, AsmError> {
+        self.value(operand, 0xFFFF, line).map(|v| v as u16)
+    }
+
+    fn encode(&self, st: &Statement, out: &mut Vec<u8>) -> Result<(), AsmError> {
+        let line = st.line;
+        match st.mnemonic.as_str() {
+            "DB" => {
+                for operand in &st.operands {
+                    out.push(self.value(operand, 0xFF, line)? as u8);
+                }
+            }
+            "DW" => {
+                for operand in &st.operands {
+                    let word = self.value(operand, 0xFFFF, line)? as u16;
+                    out.extend(word.to_be_bytes());
+                }
+            }
+            "DA" => out.extend(Self::string(&st.operands[0], line)?),
+            _ => {
+                let instruction = self.instruction(st)?;
+                let opcode = instruction.encode();
+                if !st.profile.supports(instruction) {
+                    return Err(error(
+                        line,
+                        format!("'{}' is not available with the current OPTION", instruction),
+                    ));
+                }
+                out.extend(opcode.to_be_bytes());
+            }
+        }
+        Ok(())
+    }
+
+    /// Traduit une ligne en `Instruction`, en validant registres et bornes.
+    fn instruction(&self, st: &Statement) -> Result<Instruction, AsmError> {
+        let line = st.line;
+        let ops: Vec<&str> = st.operands.iter().map(String::as_str).collect();
+        let upper: Vec<String> = ops.iter().map(|o| o.to_ascii_uppercase()).collect();
+        let up: Vec<&str> = upper.iter().map(String::as_str).collect();
+
+        let reg = |i: usize| -> Result<u8, AsmError> {
+            register(ops[i])
+                .ok_or_else(|| error(line, format!("expected a register, found '{}'", ops[i])))
+        };
+        let byte = |i: usize| self.value(ops[i], 0xFF, line).map(|v| v as u8);
+        let nibble = |i: usize| self.value(ops[i], 0xF, line).map(|v| v as u8);
+        let addr = |i: usize| self.value(ops[i], 0xFFF, line).map(|v| v as u16);
+        let bad = || {
+            error(
+                line,
+                format!(
+                    "invalid operands for {}: '{}'",
+                    st.mnemonic,
+                    st.operands.join(", ")
+                ),
+            )
+        };
+
+        let instruction = match (st.mnemonic.as_str(), up.as_slice()) {
+            ("CLS", []) => Instruction::Cls,
+            ("RET", []) => Instruction::Ret,
+            ("SCR", []) => Instruction::ScrollRight,
+            ("SCL", []) => Instruction::ScrollLeft,
+            ("EXIT", []) => Instruction::Exit,
+            ("LOW", []) => Instruction::Lores,
+            ("HIGH", []) => Instruction::Hires,
+            ("AUDIO", []) => Instruction::Audio,
+            ("SCD", [_]) => Instruction::ScrollDown { n: nibble(0)? },
+            ("SCU", [_]) => Instruction::ScrollUp { n: nibble(0)? },
+            ("PLANE", [_]) => Instruction::Plane { x: nibble(0)? },
+            ("SYS", [_]) => Instruction::Sys { nnn: addr(0)? },
+            ("JP", ["V0", _]) => Instruction::JpV0 {
+                x: 0,
+                nnn: addr(1)?,
+            },
+            ("JP", [_]) => Instruction::Jp { nnn: addr(0)? },
+            ("CALL", [_]) => Instruction::Call { nnn: addr(0)? },
+            ("SE", [_, y]) if register(y).is_some() => Instruction::SeReg {
+                x: reg(0)?,
+                y: reg(1)?,
+            },
+            ("SE", [_, _]) => Instruction::SeImm {
+                x: reg(0)?,
+                kk: byte(1)?,
+            },
+            ("SNE", [_, y]) if register(y).is_some() => Instruction::SneReg {
+                x: reg(0)?,
+                y: reg(1)?,
+            },
+            ("SNE", [_, _]) => Instruction::SneImm {
+                x: reg(0)?,
+                kk: byte(1)?,
+            },
+            ("ADD", ["I", _]) => Instruction::AddI { x: reg(1)? },
+            ("ADD", [_, y]) if register(y).is_some() => Instruction::AddReg {
+                x: reg(0)?,
+                y: reg(1)?,
+            },
+            ("ADD", [_, _]) => Instruction::AddImm {
+                x: reg(0)?,
+                kk: byte(1)?,
+            },
+            ("OR", [_, _]) => Instruction::Or {
+                x: reg(0)?,
+                y: reg(1)?,
+            },
+            ("AND", [_, _]) => Instruction::And {
+                x: reg(0)?,
+                y: reg(1)?,
+            },
+            ("XOR", [_, _]) => Instruction::Xor {
+                x: reg(0)?,
+                y: reg(1)?,
+            },
+            ("SUB", [_, _]) => Instruction::Sub {
+                x: reg(0)?,
+                y: reg(1)?,
+            },
+            ("SUBN", [_, _]) => Instruction::Subn {
+                x: reg(0)?,
+                y: reg(1)?,
+            },
+            ("SHR", [_]) => Instruction::Shr { x: reg(0)?, y: 0 },
+            ("SHR", [_, _]) => Instruction::Shr {
+                x: reg(0)?,
+                y: reg(1)?,
+            },
+            ("SHL", [_]) => Instruction::Shl { x: reg(0)?, y: 0 },
+            ("SHL", [_, _]) => Instruction::Shl {
+                x: reg(0)?,
+                y: reg(1)?,
+            },
+            ("RND", [_, _]) => Instruction::Rnd {
+                x: reg(0)?,
+                kk: byte(1)?,
+            },
+            ("DRW", [_, _, _]) => Instruction::Drw {
+                x: reg(0)?,
+                y: reg(1)?,
+                n: nibble(2)?,
+            },
+            ("SKP", [_]) => Instruction::Skp { x: reg(0)? },
+            ("SKNP", [_]) => Instruction::Sknp { x: reg(0)? },
+            ("SAVE", [_, _]) => Instruction::SaveRange {
+                x: reg(0)?,
+                y: reg(1)?,
+            },
+            ("LOAD", [_, _]) => Instruction::LoadRange {
+                x: reg(0)?,
+                y: reg(1)?,
+            },
+            ("PITCH", [_]) => Instruction::Pitch { x: reg(0)? },
+            ("LD", ["I", "LONG"]) => Instruction::LdILong,
+            ("LD", ["I", _]) => Instruction::LdI { nnn: addr(1)? },
+            ("LD", ["DT", _]) => Instruction::LdDtVx { x: reg(1)? },
+            ("LD", ["ST", _]) => Instruction::LdStVx { x: reg(1)? },
+            ("LD", ["F", _]) => Instruction::LdF { x: reg(1)? },
+            ("LD", ["HF", _]) => Instruction::LdHf { x: reg(1)? },
+            ("LD", ["B", _]) => Instruction::LdB { x: reg(1)? },
+            ("LD", ["[I]", _]) => Instruction::LdIVx { x: reg(1)? },
+            ("LD", ["R", _]) => Instruction::LdRVx { x: reg(1)? },
+            ("LD", [_, "DT"]) => Instruction::LdVxDt { x: reg(0)? },
+            ("LD", [_, "K"]) => Instruction::LdVxK { x: reg(0)? },
+            ("LD", [_, "[I]"]) => Instruction::LdVxI { x: reg(0)? },
+            ("LD", [_, "R"]) => Instruction::LdVxR { x: reg(0)? },
+            ("LD", [_, y]) if register(y).is_some() => Instruction::LdReg {
+                x: reg(0)?,
+                y: reg(1)?,
+            },
+            ("LD", [_, _]) => Instruction::LdImm {
+                x: reg(0)?,
+                kk: byte(1)?,
+            },
+            _ => return Err(bad()),
+        };
+        Ok(instruction)
+    }
+}
+
+impl fmt::Display for AsmError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl std::error::Error for AsmError {}
@@ -0,0 +1,193 @@
+use super::schema::{AsmError, Assembler, Symbol, MAX_EQU_DEPTH};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Token {
+    Num(i64),
+    Ident(String),
+    Op(char),
+    Open,
+    Close,
+}
+
+/// Priorité des opérateurs binaires, la plus forte en dernier.
+fn precedence(op: char) -> Option<u8> {
+    match op {
+        // `\` divise tout ce qui précède : `MAZEEND - MAZE \ 4` dans BLINKY.SRC
+        '\\' => Some(0),
+        '|' => Some(1),
+        '^' => Some(2),
+        '&' => Some(3),
+        '<' | '>' => Some(4),
+        '+' | '-' => Some(5),
+        '*' | '/' | '%' => Some(6),
+        _ => None,
+    }
+}
+
+/// Nombre CHIPPER : décimal, `#` hexadécimal, `$` binaire où `.` vaut 0.
+pub fn parse_number(text: &str) -> Option<i64> {
+    if let Some(hex) = text.strip_prefix('#') {
+        i64::from_str_radix(hex, 16).ok()
+    } else if let Some(bin) = text.strip_prefix('$') {
+        let bits: String = bin
+            .chars()
+            .map(|c| if c == '.' { '0' } else { c })
+            .collect();
+        i64::from_str_radix(&bits, 2).ok()
+    } else {
+        text.parse().ok()
+    }
+}
+
+fn tokenize(text: &str) -> Result<Vec<Token>, String> {
+    let chars: Vec<char> = text.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        if c.is_whitespace() {
+            i += 1;
+            continue;
+        }
+        let word_char = |c: char| c.is_ascii_alphanumeric() || c == '_' || c == '.';
+        if c == '#' || c == '$' || c.is_ascii_alphanumeric() || c == '_' {
+            let start = i;
+            i += 1;
+            while i < chars.len() && word_char(chars[i]) {
+                i += 1;
+            }
+            let word: String = chars[start..i].iter().collect();
+            if c == '#' || c == '$' || c.is_ascii_digit() {
+                let value = parse_number(&word).ok_or(format!("invalid number '{}'", word))?;
+                tokens.push(Token::Num(value));
+            } else {
+                tokens.push(Token::Ident(word.to_ascii_uppercase()));
+            }
+            continue;
+        }
+        tokens.push(match c {
+            '(' => Token::Open,
+            ')' => Token::Close,
+            '+' | '-' | '*' | '/' | '\\' | '%' | '<' | '>' | '&' | '|' | '^' | '~' => Token::Op(c),
+            _ => return Err(format!("unexpected character '{}'", c)),
+        });
+        i += 1;
+    }
+    Ok(tokens)
+}
+
+/// Analyseur à priorités sur la liste de jetons.
+struct Parser<'a> {
+    tokens: Vec<Token>,
+    pos: usize,
+    asm: &'a Assembler,
+    line: usize,
+    depth: usize,
+}
+
+impl Parser<'_> {
+    fn error(&self, message: String) -> AsmError {
+        AsmError {
+            line: self.line,
+            message,
+        }
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn binary(&mut self, min: u8) -> Result<i64, AsmError> {
+        let mut left = self.unary()?;
+        while let Some(Token::Op(op)) = self.tokens.get(self.pos).cloned() {
+            let Some(prec) = precedence(op).filter(|&p| p >= min) else {
+                break;
+            };
+            self.pos += 1;
+            let right = self.binary(prec + 1)?;
+            left = match op {
+                '|' => left | right,
+                '^' => left ^ right,
+                '&' => left & right,
+                '<' => left.checked_shl(right as u32).unwrap_or(0),
+                '>' => left.checked_shr(right as u32).unwrap_or(0),
+                '+' => left.wrapping_add(right),
+                '-' => left.wrapping_sub(right),
+                '*' => left.wrapping_mul(right),
+                _ if right == 0 => return Err(self.error("division by zero".to_string())),
+                '%' => left % right,
+                _ => left / right,
+            };
+        }
+        Ok(left)
+    }
+
+    fn unary(&mut self) -> Result<i64, AsmError> {
+        match self.next() {
+            Some(Token::Op('-')) => Ok(self.unary()?.wrapping_neg()),
+            Some(Token::Op('+')) => self.unary(),
+            Some(Token::Op('~')) => Ok(!self.unary()?),
+            Some(Token::Num(value)) => Ok(value),
+            Some(Token::Ident(name)) => self.asm.symbol(&name, self.line, self.depth),
+            Some(Token::Open) => {
+                let value = self.binary(0)?;
+                match self.next() {
+                    Some(Token::Close) => Ok(value),
+                    _ => Err(self.error("missing ')'".to_string())),
+                }
+            }
+            Some(token) => Err(self.error(format!("unexpected {:?} in expression", token))),
+            None => Err(self.error("incomplete expression".to_string())),
+        }
+    }
+}
+
+impl Assembler {
+    /// Évalue une expression CHIPPER (`-SPRITEJUMP & MASKBYTE`, `CODE < 4 | HUNT`).
+    pub fn eval(&self, text: &str, line: usize) -> Result<i64, AsmError> {
+        self.eval_depth(text, line, 0)
+    }
+
+    fn eval_depth(&self, text: &str, line: usize, depth: usize) -> Result<i64, AsmError> {
+        let tokens = tokenize(text).map_err(|message| AsmError { line, message })?;
+        if tokens.is_empty() {
+            return Err(AsmError {
+                line,
+                message: "missing value".to_string(),
+            });
+        }
+        let mut parser = Parser {
+            tokens,
+            pos: 0,
+            asm: self,
+            line,
+            depth,
+        };
+        let value = parser.binary(0)?;
+        if parser.pos < parser.tokens.len() {
+            return Err(AsmError {
+                line,
+                message: format!("unexpected text in expression '{}'", text),
+            });
+        }
+        Ok(value)
+    }
+
+    fn symbol(&self, name: &str, line: usize, depth: usize) -> Result<i64, AsmError> {
+        match self.symbols.get(name) {
+            Some(Symbol::Value(value)) => Ok(*value),
+            Some(Symbol::Expr { .. }) if depth >= MAX_EQU_DEPTH => Err(AsmError {
+                line,
+                message: format!("symbol '{}' is defined in terms of itself", name),
+            }),
+            Some(Symbol::Expr { text, line: at }) => self.eval_depth(text, *at, depth + 1),
+            None => Err(AsmError {
+                line,
+                message: format!("undefined symbol '{}'", name),
+            }),
+        }
+    }
+}
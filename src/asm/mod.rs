@@ -0,0 +1,4 @@
+#[allow(clippy::module_inception)]
+pub mod asm;
+pub mod expr;
+pub mod schema;
@@ -0,0 +1,45 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::cpu::schema::{Profile, START_ADRR};
+
+/// Adresse du premier octet produit, le binaire commence toujours à 0x200.
+pub const ORIGIN: u16 = START_ADRR as u16;
+
+/// Profondeur maximale de symboles définis les uns par rapport aux autres.
+pub const MAX_EQU_DEPTH: usize = 32;
+
+/// Erreur d'assemblage, avec le numéro de ligne (à partir de 1) dans le source.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AsmError {
+    pub line: usize,
+    pub message: String,
+}
+
+/// Valeur d'un symbole : adresse d'étiquette, ou expression `EQU` évaluée à la demande
+/// pour pouvoir utiliser des étiquettes définies plus loin.
+#[derive(Clone, Debug)]
+pub enum Symbol {
+    Value(i64),
+    Expr { text: String, line: usize },
+}
+
+/// Instruction ou directive de données retenue par la première passe.
+#[derive(Clone, Debug)]
+pub struct Statement {
+    pub line: usize,
+    pub addr: u16,
+    pub mnemonic: String,
+    pub operands: Vec<String>,
+    pub profile: Profile, // jeu d'instructions autorisé par OPTION à cet endroit
+}
+
+/// Assembleur CHIPPER en deux passes : adresses et symboles, puis encodage.
+#[derive(Clone, Debug)]
+pub struct Assembler {
+    pub symbols: HashMap<String, Symbol>,
+    pub defines: HashSet<String>, // noms testés par IFDEF/IFUND
+    pub statements: Vec<Statement>,
+    pub profile: Profile,
+    pub align: bool, // ALIGN ON : instructions et DW sur une adresse paire
+    pub addr: u16,
+}
@@ -0,0 +1,70 @@
+use super::schema::{
+    AudioOutput, NullAudio, PatternWave, RecordingAudio, SquareWave, Tone, PATTERN_BITS,
+    PATTERN_RATE,
+};
+
+impl AudioOutput for NullAudio {
+    fn set_beep(&mut self, _active: bool) {}
+}
+
+impl AudioOutput for RecordingAudio {
+    fn set_beep(&mut self, active: bool) {
+        self.frames.borrow_mut().push(active);
+    }
+
+    fn set_tone(&mut self, tone: Tone) {
+        self.tones.borrow_mut().push(tone);
+    }
+}
+
+impl Tone {
+    /// Son d'un motif XO-CHIP : la hauteur 64 lit 4000 bits par seconde,
+    /// chaque pas de 48 double ou divise par deux ce débit.
+    pub fn pattern(bits: [u8; 16], pitch: u8) -> Self {
+        let rate = PATTERN_RATE * 2f32.powf((pitch as f32 - 64.0) / 48.0);
+        Tone::Pattern { bits, rate }
+    }
+}
+
+impl SquareWave {
+    pub fn new(frequency: f32, volume: f32, sample_rate: i32) -> Self {
+        SquareWave {
+            phase_inc: frequency / sample_rate as f32,
+            phase: 0.0,
+            volume: volume.clamp(0.0, 1.0),
+        }
+    }
+
+    /// Remplit `out` avec les échantillons suivants de l'onde.
+    pub fn fill(&mut self, out: &mut [f32]) {
+        for sample in out.iter_mut() {
+            *sample = if self.phase < 0.5 {
+                self.volume
+            } else {
+                -self.volume
+            };
+            self.phase = (self.phase + self.phase_inc) % 1.0;
+        }
+    }
+}
+
+impl PatternWave {
+    pub fn new(bits: [u8; 16], rate: f32, volume: f32, sample_rate: i32) -> Self {
+        PatternWave {
+            bits,
+            phase_inc: rate / sample_rate as f32,
+            phase: 0.0,
+            volume: volume.clamp(0.0, 1.0),
+        }
+    }
+
+    /// Remplit `out` avec les échantillons suivants du motif.
+    pub fn fill(&mut self, out: &mut [f32]) {
+        for sample in out.iter_mut() {
+            let bit = self.phase as usize;
+            let set = self.bits[bit / 8] & (0x80 >> (bit % 8)) != 0;
+            *sample = if set { self.volume } else { -self.volume };
+            self.phase = (self.phase + self.phase_inc) % PATTERN_BITS;
+        }
+    }
+}
@@ -0,0 +1,52 @@
+use std::{cell::RefCell, rc::Rc};
+
+pub const DEFAULT_FREQUENCY: f32 = 440.0; // fréquence du bip en Hz
+pub const DEFAULT_VOLUME: f32 = 0.25; // amplitude entre 0.0 et 1.0
+pub const SAMPLE_RATE: i32 = 44100;
+pub const PATTERN_RATE: f32 = 4000.0; // bits par seconde du motif XO-CHIP à la hauteur 64
+pub const PATTERN_BITS: f32 = 128.0; // longueur du motif XO-CHIP en bits
+
+/// Sortie audio pilotée par le timer sonore : le bip joue tant que `sound_count` est non nul.
+pub trait AudioOutput {
+    fn set_beep(&mut self, active: bool);
+
+    /// Change le son joué par le bip. Les sorties qui ne savent faire qu'un bip l'ignorent.
+    fn set_tone(&mut self, _tone: Tone) {}
+}
+
+/// Son joué pendant que le timer sonore tourne.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Tone {
+    /// Onde carrée à la fréquence choisie par le frontend.
+    #[default]
+    Beep,
+    /// Motif 1 bit XO-CHIP (F002) lu en boucle à `rate` bits par seconde (FX3A).
+    Pattern { bits: [u8; 16], rate: f32 },
+}
+
+/// Sortie muette, utilisée par défaut et en mode headless.
+pub struct NullAudio;
+
+/// Garde l'état et le son du bip à chaque frame, pour les tests et les exécutions sans son.
+#[derive(Clone, Default)]
+pub struct RecordingAudio {
+    pub frames: Rc<RefCell<Vec<bool>>>,
+    pub tones: Rc<RefCell<Vec<Tone>>>,
+}
+
+/// Générateur d'onde carrée indépendant du backend.
+#[derive(Clone, Copy, Debug)]
+pub struct SquareWave {
+    pub phase_inc: f32,
+    pub phase: f32,
+    pub volume: f32,
+}
+
+/// Lecteur du motif XO-CHIP, bit de poids fort en premier. `phase` compte en bits.
+#[derive(Clone, Copy, Debug)]
+pub struct PatternWave {
+    pub bits: [u8; 16],
+    pub phase_inc: f32,
+    pub phase: f32,
+    pub volume: f32,
+}
@@ -0,0 +1,270 @@
+use std::{collections::HashSet, fs, path::PathBuf};
+
+use chip_8::{
+    display::{
+        palette::{parse_color, parse_palette},
+        schema::{Palette, DEFAULT_PALETTE},
+    },
+    input::schema::{GamepadMapping, DEFAULT_THRESHOLD, MAX_PLAYERS},
+    rom::schema::STDIN_PATH,
+};
+
+use super::schema::{
+    DisplayConfig, FileConfig, GamepadConfig, HostKeys, KeyboardConfig, Preset, AZERTY, CONFIG_DIR,
+    CONFIG_FILE, DVORAK, QWERTY, ROM_CONFIG_EXT,
+};
+
+impl FileConfig {
+    /// Charge la configuration globale puis celle du jeu, qui la complète.
+    /// Un fichier absent n'est pas une erreur.
+    pub fn load(rom_path: &str) -> Result<Self, String> {
+        let mut config = FileConfig::default();
+        for path in [global_path(), rom_config_path(rom_path)]
+            .into_iter()
+            .flatten()
+        {
+            if let Some(file) = Self::read(&path)? {
+                config.merge(file);
+            }
+        }
+        // préréglage et touches CHIP-8 vérifiés même sans fenêtre
+        config.keyboard.bindings()?;
+        config.gamepad.mappings()?;
+        config.gamepad.threshold()?;
+        config.display.palette()?;
+        Ok(config)
+    }
+
+    fn read(path: &PathBuf) -> Result<Option<Self>, String> {
+        let text = match fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(format!("cannot read {}: {}", path.display(), e)),
+        };
+        toml::from_str(&text)
+            .map(Some)
+            .map_err(|e| format!("invalid config {}: {}", path.display(), e))
+    }
+
+    fn merge(&mut self, other: FileConfig) {
+        if other.keyboard.preset.is_some() {
+            self.keyboard.preset = other.keyboard.preset;
+        }
+        self.keyboard.keys.extend(other.keyboard.keys);
+        if other.gamepad.threshold.is_some() {
+            self.gamepad.threshold = other.gamepad.threshold;
+        }
+        self.gamepad.player1.extend(other.gamepad.player1);
+        self.gamepad.player2.extend(other.gamepad.player2);
+        let display = other.display;
+        if display.palette.is_some() {
+            self.display.palette = display.palette;
+        }
+        if display.background.is_some() {
+            self.display.background = display.background;
+        }
+        if display.foreground.is_some() {
+            self.display.foreground = display.foreground;
+        }
+    }
+}
+
+/// `$XDG_CONFIG_HOME/chip-8/config.toml`, ou son équivalent hors Linux.
+pub fn global_path() -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join(CONFIG_DIR).join(CONFIG_FILE))
+}
+
+/// `TETRIS.toml` pour `TETRIS.ch8`.
+pub fn rom_config_path(rom_path: &str) -> Option<PathBuf> {
+    (rom_path != STDIN_PATH).then(|| PathBuf::from(rom_path).with_extension(ROM_CONFIG_EXT))
+}
+
+fn preset(name: &str) -> Result<&'static Preset, String> {
+    match name.to_ascii_lowercase().as_str() {
+        "qwerty" => Ok(&QWERTY),
+        "azerty" => Ok(&AZERTY),
+        "dvorak" => Ok(&DVORAK),
+        _ => Err(format!(
+            "unknown keyboard preset '{}', expected qwerty, azerty or dvorak",
+            name
+        )),
+    }
+}
+
+fn chip8_key(key: &str) -> Result<u8, String> {
+    u8::from_str_radix(key.trim_start_matches("0x"), 16)
+        .ok()
+        .filter(|&k| k < 16)
+        .ok_or_else(|| format!("'{}' is not a CHIP-8 key (0 to F)", key))
+}
+
+impl KeyboardConfig {
+    /// Paires (touche hôte, touche CHIP-8) : le préréglage, où chaque touche redéfinie
+    /// remplace toutes ses touches hôtes. Une touche hôte reprise par une redéfinition
+    /// quitte sa place dans le préréglage.
+    pub fn bindings(&self) -> Result<Vec<(String, u8)>, String> {
+        let preset = preset(self.preset.as_deref().unwrap_or("qwerty"))?;
+        let taken: HashSet<String> = self
+            .keys
+            .values()
+            .flat_map(|names| match names {
+                HostKeys::One(name) => vec![name.to_uppercase()],
+                HostKeys::Many(names) => names.iter().map(|n| n.to_uppercase()).collect(),
+            })
+            .collect();
+        let mut hosts: Vec<Vec<String>> = preset
+            .iter()
+            .map(|names| {
+                names
+                    .iter()
+                    .filter(|n| !taken.contains(&n.to_uppercase()))
+                    .map(|n| n.to_string())
+                    .collect()
+            })
+            .collect();
+
+        for (key, names) in &self.keys {
+            hosts[chip8_key(key)? as usize] = match names {
+                HostKeys::One(name) => vec![name.clone()],
+                HostKeys::Many(names) => names.clone(),
+            };
+        }
+
+        Ok(hosts
+            .into_iter()
+            .enumerate()
+            .flat_map(|(key, names)| names.into_iter().map(move |n| (n, key as u8)))
+            .collect())
+    }
+}
+
+impl GamepadConfig {
+    /// Association de chaque joueur ; un joueur sans contrôle configuré garde
+    /// l'association standard.
+    pub fn mappings(&self) -> Result<[GamepadMapping; MAX_PLAYERS], String> {
+        let mut mappings: [GamepadMapping; MAX_PLAYERS] = Default::default();
+        for (mapping, controls) in mappings.iter_mut().zip([&self.player1, &self.player2]) {
+            if controls.is_empty() {
+                *mapping = GamepadMapping::standard();
+                continue;
+            }
+            for (control, key) in controls {
+                mapping.bindings.insert(control.parse()?, chip8_key(key)?);
+            }
+        }
+        Ok(mappings)
+    }
+
+    /// Seuil des axes sur l'échelle SDL (0 à 32767).
+    pub fn threshold(&self) -> Result<i16, String> {
+        match self.threshold {
+            None => Ok(DEFAULT_THRESHOLD),
+            Some(t) if (0.0..1.0).contains(&t) => Ok((t * i16::MAX as f32) as i16),
+            Some(t) => Err(format!("gamepad threshold {} is not between 0 and 1", t)),
+        }
+    }
+}
+
+impl DisplayConfig {
+    pub fn palette(&self) -> Result<Palette, String> {
+        let mut palette = match &self.palette {
+            Some(spec) => parse_palette(spec)?,
+            None => DEFAULT_PALETTE,
+        };
+        if let Some(color) = &self.background {
+            palette[0] = parse_color(color)?;
+        }
+        if let Some(color) = &self.foreground {
+            palette[1] = parse_color(color)?;
+        }
+        Ok(palette)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(text: &str) -> FileConfig {
+        toml::from_str(text).unwrap()
+    }
+
+    fn hosts(bindings: &[(String, u8)], key: u8) -> Vec<&str> {
+        bindings
+            .iter()
+            .filter(|(_, k)| *k == key)
+            .map(|(name, _)| name.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn preset_keys_are_replaced_by_overrides() {
+        let config = parse("[keyboard]\npreset = \"azerty\"\n[keyboard.keys]\n1 = \"K\"\n");
+        let bindings = config.keyboard.bindings().unwrap();
+        assert_eq!(hosts(&bindings, 1), ["K"]);
+        assert_eq!(hosts(&bindings, 2), ["é", "2"]);
+    }
+
+    #[test]
+    fn overridden_host_keys_leave_the_preset() {
+        // Q est la touche 4 en QWERTY, elle ne déclenche plus que la touche A
+        let config = parse("[keyboard.keys]\nA = [\"q\", \"Space\"]\n");
+        let bindings = config.keyboard.bindings().unwrap();
+        assert_eq!(hosts(&bindings, 0xA), ["q", "Space"]);
+        assert!(hosts(&bindings, 4).is_empty());
+        assert_eq!(hosts(&bindings, 5), ["W"]);
+    }
+
+    #[test]
+    fn bad_presets_and_keys_are_reported() {
+        let config = parse("[keyboard]\npreset = \"colemak\"\n");
+        assert!(config.keyboard.bindings().unwrap_err().contains("colemak"));
+        let config = parse("[keyboard.keys]\n10 = \"K\"\n");
+        assert!(config.keyboard.bindings().unwrap_err().contains("'10'"));
+        assert!(toml::from_str::<FileConfig>("[screen]\n").is_err());
+    }
+
+    #[test]
+    fn rom_config_completes_the_global_one() {
+        let dir = std::env::temp_dir().join(format!("chip8-config-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let global = dir.join(CONFIG_FILE);
+        let rom = dir.join("GAME.toml");
+        fs::write(
+            &global,
+            "[keyboard]\npreset = \"dvorak\"\n[keyboard.keys]\n1 = \"K\"\n2 = \"L\"\n",
+        )
+        .unwrap();
+        fs::write(&rom, "[keyboard.keys]\n2 = \"M\"\n").unwrap();
+
+        let mut config = FileConfig::default();
+        for path in [&global, &rom, &dir.join("MISSING.toml")] {
+            if let Some(file) = FileConfig::read(path).unwrap() {
+                config.merge(file);
+            }
+        }
+        fs::remove_dir_all(&dir).unwrap();
+
+        let bindings = config.keyboard.bindings().unwrap();
+        assert_eq!(config.keyboard.preset.as_deref(), Some("dvorak"));
+        assert_eq!(hosts(&bindings, 1), ["K"]);
+        assert_eq!(hosts(&bindings, 2), ["M"]);
+        assert_eq!(hosts(&bindings, 0), ["Q"]);
+    }
+
+    #[test]
+    fn rom_preset_replaces_the_global_one() {
+        let mut config = parse("[keyboard]\npreset = \"azerty\"\n");
+        config.merge(parse("[keyboard]\npreset = \"qwerty\"\n"));
+        assert_eq!(config.keyboard.preset.as_deref(), Some("qwerty"));
+    }
+
+    #[test]
+    fn rom_config_sits_next_to_the_rom() {
+        assert_eq!(
+            rom_config_path("games/TETRIS.ch8"),
+            Some(PathBuf::from("games/TETRIS.toml"))
+        );
+        assert_eq!(rom_config_path(STDIN_PATH), None);
+    }
+}
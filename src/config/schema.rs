@@ -0,0 +1,116 @@
+use std::collections::BTreeMap;
+
+use serde::Deserialize;
+
+pub const CONFIG_DIR: &str = "chip-8"; // sous-dossier du dossier de configuration XDG
+pub const CONFIG_FILE: &str = "config.toml";
+pub const ROM_CONFIG_EXT: &str = "toml"; // `TETRIS.toml` à côté de `TETRIS.ch8`
+
+/// Réglages lus dans le config.toml global puis complétés par celui du jeu.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct FileConfig {
+    pub keyboard: KeyboardConfig,
+    pub gamepad: GamepadConfig,
+    pub display: DisplayConfig,
+}
+
+/// Couleurs de l'écran : une palette, puis le fond ou les pixels allumés remplacés.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DisplayConfig {
+    /// Préréglage (`amber`, `green`, `lcd`, `octo`) ou 2 ou 4 couleurs `#RRGGBB`.
+    pub palette: Option<String>,
+    pub background: Option<String>,
+    pub foreground: Option<String>,
+}
+
+/// Disposition du clavier hôte : un préréglage, puis des touches CHIP-8 redéfinies.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct KeyboardConfig {
+    pub preset: Option<String>,
+    /// Touche CHIP-8 en hexadécimal (`"5"`, `"A"`) vers un ou plusieurs noms de touches SDL.
+    pub keys: BTreeMap<String, HostKeys>,
+}
+
+/// Manettes : contrôle SDL (`dpup`, `a`, `leftx-`) vers une touche CHIP-8, par joueur.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct GamepadConfig {
+    /// Fraction de la course d'un axe (0 à 1) au-delà de laquelle il appuie.
+    pub threshold: Option<f32>,
+    pub player1: BTreeMap<String, String>,
+    pub player2: BTreeMap<String, String>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(untagged)]
+pub enum HostKeys {
+    One(String),
+    Many(Vec<String>),
+}
+
+/// Touches hôtes (noms SDL) de chaque touche CHIP-8, de 0 à F.
+pub type Preset = [&'static [&'static str]; 16];
+
+/// 1234 / QWER / ASDF / ZXCV.
+pub const QWERTY: Preset = [
+    &["X"],
+    &["1"],
+    &["2"],
+    &["3"],
+    &["Q"],
+    &["W"],
+    &["E"],
+    &["A"],
+    &["S"],
+    &["D"],
+    &["Z"],
+    &["C"],
+    &["4"],
+    &["R"],
+    &["F"],
+    &["V"],
+];
+
+/// Mêmes positions sur un clavier AZERTY. La rangée du haut est liée à ses symboles sans Maj
+/// (`&`, `é`, `"`, `'`), le keycode que SDL donne pour ces touches, et aussi aux chiffres 1 à 4.
+pub const AZERTY: Preset = [
+    &["X"],
+    &["&", "1"],
+    &["é", "2"],
+    &["\"", "3"],
+    &["A"],
+    &["Z"],
+    &["E"],
+    &["Q"],
+    &["S"],
+    &["D"],
+    &["W"],
+    &["C"],
+    &["'", "4"],
+    &["R"],
+    &["F"],
+    &["V"],
+];
+
+/// Mêmes positions sur un clavier Dvorak.
+pub const DVORAK: Preset = [
+    &["Q"],
+    &["1"],
+    &["2"],
+    &["3"],
+    &["'"],
+    &[","],
+    &["."],
+    &["A"],
+    &["O"],
+    &["E"],
+    &[";"],
+    &["J"],
+    &["4"],
+    &["P"],
+    &["U"],
+    &["K"],
+];
@@ -1,17 +1,16 @@
-use std::{
-    fs::File,
-    io::{self, Read},
-};
-
-use crate::display::schema::ContextPixels;
-use rand::random;
+use crate::audio::schema::Tone;
+use crate::display::schema::FrameBuffer;
+use crate::rom::{loader::read_rom, schema::LoadError};
 
-use super::schema::{Jump, Keyboard, CHIP8_FONTSET, CPU, MEM_SIZE, NBR_OPCODE, START_ADRR};
+use super::schema::{
+    Chip8Error, Instruction, Keyboard, Keymap, OsRandom, Quirks, BIG_FONT_ADDR, CHIP8_FONTSET, CPU,
+    DEFAULT_PITCH, MEM_SIZE, SCHIP_FONTSET, START_ADRR,
+};
 
 impl CPU {
-    pub fn new(debug: bool) -> Self {
+    pub fn new(debug: bool, quirks: Quirks) -> Self {
         CPU {
-            mem: [0u8; MEM_SIZE],
+            mem: vec![0u8; MEM_SIZE],
             V: [0u8; 16],
             stack: [0u16; 16],
             pc: START_ADRR as u16,
@@ -20,13 +19,34 @@ impl CPU {
             sound_count: 0,
             I: 0,
             debug,
+            quirks,
+            rpl: [0u8; 16],
+            exited: false,
+            audio_pattern: None,
+            pitch: DEFAULT_PITCH,
+            rng: Box::new(OsRandom),
         }
     }
 
+    /// Redimensionne la mémoire (XO_MEM_SIZE pour XO-CHIP), le contenu existant est conservé.
+    pub fn set_mem_size(&mut self, size: usize) {
+        self.mem.resize(size, 0);
+    }
+
+    /// Saute l'instruction suivante, qui occupe 4 octets si c'est un F000 NNNN
+    /// et que l'interpréteur émulé connaît cette instruction.
+    fn skip_next(&mut self) {
+        let next = self.pc as usize + 2;
+        let is_long = self.quirks.supports(Instruction::LdILong)
+            && self.mem.get(next) == Some(&0xF0)
+            && self.mem.get(next + 1) == Some(&0x00);
+        self.pc = self.pc.wrapping_add(if is_long { 4 } else { 2 });
+    }
+
     pub fn init_memory(&mut self) {
-        for i in 0..CHIP8_FONTSET.len() {
-            self.mem[i] = CHIP8_FONTSET[i];
-        }
+        self.mem[..CHIP8_FONTSET.len()].copy_from_slice(&CHIP8_FONTSET);
+        self.mem[BIG_FONT_ADDR..BIG_FONT_ADDR + SCHIP_FONTSET.len()]
+            .copy_from_slice(&SCHIP_FONTSET);
     }
 
     pub fn countdown(&mut self) {
@@ -38,61 +58,110 @@ impl CPU {
         }
     }
 
-    pub fn get_opcode(&mut self) -> u16 {
-        return ((self.mem[self.pc as usize] as u16) << 8)
-            + self.mem[(self.pc + 1) as usize] as u16;
+    /// Son du timer sonore : le motif XO-CHIP une fois chargé par F002, le bip sinon.
+    pub fn tone(&self) -> Tone {
+        match self.audio_pattern {
+            Some(bits) => Tone::pattern(bits, self.pitch),
+            None => Tone::Beep,
+        }
     }
 
-    pub fn interpret(&mut self, opcode: u16, j: &Jump, display: &mut ContextPixels) {
-        // recuperation des sous partie de lopcode
-        let b3 = ((opcode & (0x0F00)) >> 8) as u8; // on prend les 4 bits, b3 représente X
-        let b2 = ((opcode & (0x00F0)) >> 4) as u8; // idem, b2 représente Y
-        let b1 = (opcode & (0x000F)) as u8; // on prend les 4 bits de poids faible
-        let nnn = opcode & 0x0FFF; // les 12 dernier bits | 4 premier = [numeros d'instruction (2)] | 12 dernier = [adrr]
-        let kk = (opcode & 0x00FF) as u8; // les 8 derniers bits
-        let mut can_iter = true;
+    pub fn get_opcode(&self) -> u16 {
+        ((self.mem[self.pc as usize] as u16) << 8) + self.mem[(self.pc + 1) as usize] as u16
+    }
+
+    /// Vérifie que l'opcode à `pc` est entièrement dans la mémoire.
+    pub fn check_fetch(&self) -> Result<(), Chip8Error> {
+        if self.pc as usize + 1 < self.mem.len() {
+            Ok(())
+        } else {
+            Err(Chip8Error::FetchOutOfBounds {
+                pc: self.pc,
+                partial: self.mem.get(self.pc as usize).copied(),
+            })
+        }
+    }
 
-        let b4 = j.get_action(opcode);
+    /// Vérifie que `len` octets à partir de `addr` sont accessibles, sinon une faute
+    /// est levée pour l'instruction courante.
+    fn check_mem(&self, addr: usize, len: usize) -> Result<(), Chip8Error> {
+        if addr + len <= self.mem.len() {
+            Ok(())
+        } else {
+            Err(Chip8Error::MemoryOutOfBounds {
+                pc: self.pc,
+                opcode: self.get_opcode(),
+                addr: addr.max(self.mem.len()),
+            })
+        }
+    }
+
+    /// Décode puis exécute `opcode`.
+    pub fn interpret(
+        &mut self,
+        opcode: u16,
+        display: &mut FrameBuffer,
+        keyboard: &mut Keyboard,
+    ) -> Result<(), Chip8Error> {
+        self.execute(Instruction::decode(opcode), display, keyboard)
+    }
+
+    /// Exécute une instruction. En cas de faute, `pc` reste sur l'instruction fautive.
+    pub fn execute(
+        &mut self,
+        instruction: Instruction,
+        display: &mut FrameBuffer,
+        keyboard: &mut Keyboard,
+    ) -> Result<(), Chip8Error> {
+        let mut can_iter = true;
 
         if self.debug {
             println!(
-                "PC={:03X} I={:03X} Opcode={:04X} V={:?} SP={} Stack={:?} Action={} | X = {} | Y = {}",
+                "PC={:03X} I={:03X} V={:?} SP={} Stack={:?} {:?}",
                 self.pc,
                 self.I,
-                opcode,
                 self.V,
                 self.sp,
                 &self.stack[..self.sp as usize],
-                b4,
-                b3,
-                b2
+                instruction
             );
         }
 
-        match b4 {
-            0 => {}                      // opcode non implementer
-            1 => display.clear_screen(), // efface l'ecran
-            2 => {
+        // extension que l'interpréteur émulé ne connaît pas : même faute qu'un opcode inconnu
+        if !self.quirks.supports(instruction) {
+            return Err(Chip8Error::UnknownOpcode {
+                pc: self.pc,
+                opcode: self.get_opcode(),
+            });
+        }
+
+        match instruction {
+            Instruction::Sys { .. } => {} // 0NNN routine machine, non émulée
+            Instruction::Cls => display.clear_screen(), // 00E0 efface l'ecran
+            Instruction::Ret => {
                 // 00EE revien du saut
-                //println!("2");
                 if self.sp == 0 {
-                    panic!("Stack underflow: retour sans appel de sous-programme")
+                    return Err(Chip8Error::StackUnderflow {
+                        pc: self.pc,
+                        opcode: self.get_opcode(),
+                    });
                 }
 
                 self.sp -= 1;
                 self.pc = self.stack[self.sp as usize];
             }
-            3 => {
+            Instruction::Jp { nnn } => {
                 // 1NNN effectue un saut à l'adresse 1NNN.
-                //println!("3");
                 self.pc = nnn;
                 can_iter = false;
             }
-            4 => {
+            Instruction::Call { nnn } => {
                 // 2NNN appelle le sous-programme en NNN, mais on revient ensuite.
-                //println!("4");
                 if self.sp >= 16 {
-                    panic!("Stack overflow: trop d'appels de sous-programmes");
+                    return Err(Chip8Error::StackOverflow {
+                        pc: self.pc,
+                        opcode: self.get_opcode(),
+                    });
                 }
 
                 self.stack[self.sp as usize] = self.pc;
@@ -101,176 +170,166 @@ impl CPU {
                 //self.pc -= 2;
                 can_iter = false;
             }
-            5 => {
+            Instruction::SeImm { x, kk } => {
                 // 3XKK saute l'instruction suivante si VX est égal à KK.
-                //println!("5");
-                if self.V[b3 as usize] == kk {
-                    self.pc += 2;
+                if self.V[x as usize] == kk {
+                    self.skip_next();
                 }
             }
-            6 => {
+            Instruction::SneImm { x, kk } => {
                 // 4XKK saute l'instruction suivante si VX et KK ne sont pas égaux.
-                //println!("6");
-                if self.V[b3 as usize] != kk {
-                    self.pc += 2;
+                if self.V[x as usize] != kk {
+                    self.skip_next();
                 }
             }
-            7 => {
+            Instruction::SeReg { x, y } => {
                 // 5XY0 saute l'instruction suivante si VX et VY sont égaux.
-                //println!("7");
-                if self.V[b3 as usize] == self.V[b2 as usize] {
-                    self.pc += 2;
+                if self.V[x as usize] == self.V[y as usize] {
+                    self.skip_next();
                 }
             }
-            8 => {
+            Instruction::LdImm { x, kk } => {
                 // 6XNN définit VX à KK.
-                //println!("8");
-                self.V[b3 as usize] = kk;
+                self.V[x as usize] = kk;
             }
-            9 => {
+            Instruction::AddImm { x, kk } => {
                 // 7XKK ajoute KK à VX.
-                //println!("9");
-                self.V[b3 as usize] = self.V[b3 as usize].wrapping_add(kk); // additione et evite
-                                                                            // l'overflow
+                self.V[x as usize] = self.V[x as usize].wrapping_add(kk); // additione et evite
+                                                                          // l'overflow
             }
-            10 => {
+            Instruction::LdReg { x, y } => {
                 // 8XY0 définit VX à la valeur de VY.
-                //println!("10");
-                self.V[b3 as usize] = self.V[b2 as usize];
+                self.V[x as usize] = self.V[y as usize];
             }
-            11 => {
+            Instruction::Or { x, y } => {
                 // 8XY1 définit VX à VX OR VY.
-                //println!("11");
-                self.V[b3 as usize] = self.V[b3 as usize] | self.V[b2 as usize];
+                self.V[x as usize] |= self.V[y as usize];
+                if self.quirks.logic_resets_vf {
+                    self.V[0xF] = 0;
+                }
             }
-            12 => {
+            Instruction::And { x, y } => {
                 // 8XY2 définit VX à VX AND VY.
-                //println!("12");
-                self.V[b3 as usize] = self.V[b3 as usize] & self.V[b2 as usize];
+                self.V[x as usize] &= self.V[y as usize];
+                if self.quirks.logic_resets_vf {
+                    self.V[0xF] = 0;
+                }
             }
-            13 => {
+            Instruction::Xor { x, y } => {
                 // 8XY3 définit VX à VX XOR VY.
-                //println!("13");
-                self.V[b3 as usize] = self.V[b3 as usize] ^ self.V[b2 as usize];
+                self.V[x as usize] ^= self.V[y as usize];
+                if self.quirks.logic_resets_vf {
+                    self.V[0xF] = 0;
+                }
             }
-            14 => {
+            Instruction::AddReg { x, y } => {
                 // 8XY4 ajoute VY à VX. VF est mis à 1 quand il y a un dépassement de mémoire (carry), et à 0 quand il n'y en pas.
-                //println!("14");
-                let (result, carry) = self.V[b3 as usize].overflowing_add(self.V[b2 as usize]);
-                self.V[b3 as usize] = result;
+                let (result, carry) = self.V[x as usize].overflowing_add(self.V[y as usize]);
+                self.V[x as usize] = result;
                 self.V[0xF] = if carry { 1 } else { 0 };
             }
-            15 => {
+            Instruction::Sub { x, y } => {
                 // 8XY5 VY est soustraite de VX. VF est mis à 0 quand il y a un emprunt, et à 1 quand il n'y a en pas.
-                //println!("15");
-                let (result, borrow) = self.V[b3 as usize].overflowing_sub(self.V[b2 as usize]);
-                self.V[b3 as usize] = result;
+                let (result, borrow) = self.V[x as usize].overflowing_sub(self.V[y as usize]);
+                self.V[x as usize] = result;
                 self.V[0xF] = if borrow { 0 } else { 1 };
             }
-            16 => {
+            Instruction::Shr { x, y } => {
                 // 8XY6 décale (shift) VX à droite de 1 bit. VF est fixé à la valeur du bit de poids faible de VX avant le décalage.
-                //println!("16");
-                self.V[0xF] = self.V[b3 as usize] & 0x1;
+                let src = if self.quirks.shift_uses_vy { y } else { x };
+                let flag = self.V[src as usize] & 0x1;
 
-                self.V[b3 as usize] = self.V[b3 as usize] >> 1;
+                self.V[x as usize] = self.V[src as usize] >> 1;
+                self.V[0xF] = flag;
             }
-            17 => {
+            Instruction::Subn { x, y } => {
                 // 8XY7 VX = VY - VX. VF est mis à 0 quand il y a un emprunt et à 1 quand il n'y en a pas.
-                //println!("17");
-                let (result, borrow) = self.V[b2 as usize].overflowing_sub(self.V[b3 as usize]);
-                self.V[b3 as usize] = result;
+                let (result, borrow) = self.V[y as usize].overflowing_sub(self.V[x as usize]);
+                self.V[x as usize] = result;
                 self.V[0xF] = if borrow { 0 } else { 1 };
             }
-            18 => {
+            Instruction::Shl { x, y } => {
                 // 8XYE décale (shift) VX à gauche de 1 bit. VF est fixé à la valeur du bit de poids fort de VX avant le décalage.
-                //println!("18");
-                self.V[0xF] = (self.V[b3 as usize] >> 7) & 0x1;
+                let src = if self.quirks.shift_uses_vy { y } else { x };
+                let flag = (self.V[src as usize] >> 7) & 0x1;
 
-                self.V[b3 as usize] = self.V[b3 as usize] << 1;
+                self.V[x as usize] = self.V[src as usize] << 1;
+                self.V[0xF] = flag;
             }
-            19 => {
+            Instruction::SneReg { x, y } => {
                 // 9XY0 saute l'instruction suivante si VX et VY ne sont pas égaux.
-                //println!("19");
-                if self.V[b3 as usize] != self.V[b2 as usize] {
-                    self.pc += 2;
+                if self.V[x as usize] != self.V[y as usize] {
+                    self.skip_next();
                 }
             }
-            20 => {
+            Instruction::LdI { nnn } => {
                 // ANNN affecte NNN à I.
-                //println!("20");
                 self.I = nnn;
             }
-            21 => {
-                // BNNN passe à l'adresse NNN + V0.
-                //println!("21");
-                self.pc = self.V[0] as u16 + nnn;
+            Instruction::JpV0 { x, nnn } => {
+                // BNNN passe à l'adresse NNN + V0 (ou XNN + VX selon les quirks).
+                let offset = if self.quirks.jump_uses_vx { x } else { 0 };
+                self.pc = self.V[offset as usize] as u16 + nnn;
+                can_iter = false;
             }
-            22 => {
+            Instruction::Rnd { x, kk } => {
                 // CXNN définit VX à un nombre aléatoire inférieur à NN.
-                //println!("22");
-                let r: u8 = random();
-                self.V[b3 as usize] = r & kk;
+                let r = self.rng.next_byte();
+                self.V[x as usize] = r & kk;
             }
-            23 => {
-                // DXYN dessine un sprite aux coordonnées (VX, VY).
-                //println!("23");
-                display.draw_screen(b1, b3, b2, self);
+            Instruction::Drw { x, y, n } => {
+                // DXYN dessine un sprite aux coordonnées (VX, VY), DXY0 un sprite 16x16.
+                self.check_mem(self.I as usize, display.sprite_len(n))?;
+                display.draw_screen(n, x, y, self);
             }
-            24 => {
+            Instruction::Skp { x } => {
                 // EX9E saute l'instruction suivante si la clé stockée dans VX est pressée.
-                //println!("24");
-                let key = self.V[b3 as usize];
+                let key = self.V[x as usize];
 
-                if display.keyboard.ispressed(key) {
-                    self.pc += 2;
+                if keyboard.ispressed(key) {
+                    self.skip_next();
                 }
             }
-            25 => {
+            Instruction::Sknp { x } => {
                 // EXA1 saute l'instruction suivante si la clé stockée dans VX n'est pas pressée.
-                //println!("25");
-                let key = self.V[b3 as usize];
+                let key = self.V[x as usize];
 
-                if !display.keyboard.ispressed(key) {
-                    self.pc += 2;
+                if !keyboard.ispressed(key) {
+                    self.skip_next();
                 }
             }
-            26 => {
+            Instruction::LdVxDt { x } => {
                 // FX07 définit VX à la valeur de la temporisation.
-                //println!("26");
-                self.V[b3 as usize] = self.game_count;
+                self.V[x as usize] = self.game_count;
             }
-            27 => {
+            Instruction::LdVxK { x } => {
                 // FX0A attend l'appui sur une touche et la stocke ensuite dans VX.
-                //println!("27");
                 can_iter = false;
-                if let Some(index) = display.keyboard.awaiting_key {
-                    for (i, &pressed) in display.keyboard.keys.iter().enumerate() {
+                if let Some(index) = keyboard.awaiting_key {
+                    for (i, &pressed) in keyboard.keys.iter().enumerate() {
                         if pressed {
                             self.V[index as usize] = i as u8;
-                            display.keyboard.awaiting_key = None;
+                            keyboard.awaiting_key = None;
                             can_iter = true;
                             break;
                         }
                     }
                 } else {
-                    display.keyboard.awaiting_key = Some(b3);
+                    keyboard.awaiting_key = Some(x);
                 }
             }
-            28 => {
+            Instruction::LdDtVx { x } => {
                 // FX15 définit la temporisation à VX.
-                //println!("28");
-                self.game_count = self.V[b3 as usize];
+                self.game_count = self.V[x as usize];
             }
-            29 => {
+            Instruction::LdStVx { x } => {
                 // FX18 définit la minuterie sonore à VX.
-                //println!("29");
-                self.sound_count = self.V[b3 as usize];
+                self.sound_count = self.V[x as usize];
             }
-            30 => {
+            Instruction::AddI { x } => {
                 // FX1E ajo ute à VX I. VF est mis à 1 quand il y a overflow (I+VX>0xFFF), et à 0 si tel n'est pas le cas.
-                //println!("30");
-                let vx = self.V[b3 as usize] as u16;
-                let res = self.I + vx;
+                let vx = self.V[x as usize] as u16;
+                let res = self.I.wrapping_add(vx);
 
                 if res > 0x0FFF {
                     self.V[0xF] = 1;
@@ -279,146 +338,172 @@ impl CPU {
                 }
                 self.I = res;
             }
-            31 => {
+            Instruction::LdF { x } => {
                 // FX29 définit I à l'emplacement du caractère stocké dans VX. Les caractères 0-F (en hexadécimal) sont représentés par une police 4x5.
-                //println!("31");
-                let digit = self.V[b3 as usize] as u16;
+                // seul le chiffre bas compte, comme sur le VIP et le SCHIP
+                let digit = (self.V[x as usize] & 0xF) as u16;
                 self.I = digit * 5;
             }
-            32 => {
+            Instruction::LdB { x } => {
                 // FX33 stocke dans la mémoire le code décimal représentant VX (dans I, I+1, I+2).
-                //println!("32");
-                let value = self.V[b3 as usize];
-                self.mem[self.I as usize] = value / 100;
-                self.mem[(self.I + 1) as usize] = (value / 10) % 10;
-                self.mem[(self.I + 2) as usize] = value % 10;
-            }
-            33 => {
+                let value = self.V[x as usize];
+                let addr = self.I as usize;
+                self.check_mem(addr, 3)?;
+                self.mem[addr] = value / 100;
+                self.mem[addr + 1] = (value / 10) % 10;
+                self.mem[addr + 2] = value % 10;
+            }
+            Instruction::LdIVx { x } => {
                 // FX55 stocke V0 à VX en mémoire à partir de l'adresse I.
-                //println!("33");
-                for i in 0..=b3 {
-                    self.mem[(self.I + i as u16) as usize] = self.V[i as usize];
+                self.check_mem(self.I as usize, x as usize + 1)?;
+                for i in 0..=x as usize {
+                    self.mem[self.I as usize + i] = self.V[i];
+                }
+                if self.quirks.load_store_increments_i {
+                    self.I = self.I.wrapping_add(x as u16 + 1);
                 }
             }
-            34 => {
+            Instruction::LdVxI { x } => {
                 // FX65 remplit V0 à VX avec les valeurs de la mémoire à partir de l'adresse I.
-                //println!("34");
-                for i in 0..=b3 {
-                    self.V[i as usize] = self.mem[(self.I + i as u16) as usize];
+                self.check_mem(self.I as usize, x as usize + 1)?;
+                for i in 0..=x as usize {
+                    self.V[i] = self.mem[self.I as usize + i];
                 }
+                if self.quirks.load_store_increments_i {
+                    self.I = self.I.wrapping_add(x as u16 + 1);
+                }
+            }
+            Instruction::ScrollDown { n } => {
+                // 00CN fait défiler l'écran de N lignes vers le bas.
+                display.scroll_down(n);
+            }
+            Instruction::ScrollRight => {
+                // 00FB fait défiler l'écran de 4 pixels vers la droite.
+                display.scroll_right(4);
+            }
+            Instruction::ScrollLeft => {
+                // 00FC fait défiler l'écran de 4 pixels vers la gauche.
+                display.scroll_left(4);
+            }
+            Instruction::Exit => {
+                // 00FD quitte l'interpréteur.
+                self.exited = true;
+                can_iter = false;
             }
-            _ => {
+            Instruction::Lores => {
+                // 00FE passe en basse résolution (64x32).
+                display.set_hires(false);
+            }
+            Instruction::Hires => {
+                // 00FF passe en haute résolution (128x64).
+                display.set_hires(true);
+            }
+            Instruction::LdHf { x } => {
+                // FX30 définit I à l'emplacement du chiffre VX dans la grande police 8x10.
+                let digit = (self.V[x as usize] & 0xF) as u16;
+                self.I = BIG_FONT_ADDR as u16 + digit * 10;
+            }
+            Instruction::LdRVx { x } => {
+                // FX75 sauvegarde V0 à VX dans les drapeaux RPL.
+                self.rpl[..=x as usize].copy_from_slice(&self.V[..=x as usize]);
+            }
+            Instruction::LdVxR { x } => {
+                // FX85 restaure V0 à VX depuis les drapeaux RPL.
+                self.V[..=x as usize].copy_from_slice(&self.rpl[..=x as usize]);
+            }
+            Instruction::SaveRange { x, y } => {
+                // 5XY2 stocke VX à VY en mémoire à partir de l'adresse I, sans modifier I.
+                let (x, y) = (x as usize, y as usize);
+                let regs: Vec<usize> = if x <= y {
+                    (x..=y).collect()
+                } else {
+                    (y..=x).rev().collect()
+                };
+                self.check_mem(self.I as usize, regs.len())?;
+                for (i, reg) in regs.into_iter().enumerate() {
+                    self.mem[self.I as usize + i] = self.V[reg];
+                }
+            }
+            Instruction::LoadRange { x, y } => {
+                // 5XY3 remplit VX à VY depuis la mémoire à partir de l'adresse I, sans modifier I.
+                let (x, y) = (x as usize, y as usize);
+                let regs: Vec<usize> = if x <= y {
+                    (x..=y).collect()
+                } else {
+                    (y..=x).rev().collect()
+                };
+                self.check_mem(self.I as usize, regs.len())?;
+                for (i, reg) in regs.into_iter().enumerate() {
+                    self.V[reg] = self.mem[self.I as usize + i];
+                }
+            }
+            Instruction::LdILong => {
+                // F000 NNNN charge l'adresse 16 bits suivante dans I.
+                let addr = self.pc as usize + 2;
+                self.check_mem(addr, 2)?;
+                self.I = ((self.mem[addr] as u16) << 8) | self.mem[addr + 1] as u16;
+                self.pc += 2;
+            }
+            Instruction::Plane { x } => {
+                // FN01 sélectionne les plans de dessin (masque N).
+                display.planes = x & 0x3;
+            }
+            Instruction::Audio => {
+                // F002 charge les 16 octets à l'adresse I dans le motif sonore.
+                let start = self.I as usize;
+                self.check_mem(start, 16)?;
+                self.audio_pattern = Some(self.mem[start..start + 16].try_into().unwrap());
+            }
+            Instruction::Pitch { x } => {
+                // FX3A définit la hauteur du motif sonore à VX.
+                self.pitch = self.V[x as usize];
+            }
+            Instruction::ScrollUp { n } => {
+                // 00DN fait défiler l'écran de N lignes vers le haut.
+                display.scroll_up(n);
+            }
+            Instruction::Unknown(opcode) => {
                 // Code non reconnu
-                println!("ERROR UNEXPECTED INSTRUCTION => ");
+                return Err(Chip8Error::UnknownOpcode {
+                    pc: self.pc,
+                    opcode,
+                });
             }
         }
 
         if can_iter {
-            self.pc += 2; // on avance l'index de 2 car chaque instruction prend une place de 2 cases
+            self.pc = self.pc.wrapping_add(2); // on avance l'index de 2 car chaque instruction prend une place de 2 cases
         }
+        Ok(())
     }
 
-    pub fn load_game(&mut self, path: &str) -> io::Result<()> {
-        let mut game = File::open(path)?;
-        let mut buffer = Vec::new();
-        game.read_to_end(&mut buffer)?;
+    /// Copie `rom` en mémoire à l'adresse `addr`, qui devient le point d'entrée.
+    pub fn load_rom(&mut self, rom: &[u8], addr: usize) -> Result<(), LoadError> {
+        if rom.is_empty() {
+            return Err(LoadError::Empty);
+        }
+        if addr >= self.mem.len() {
+            return Err(LoadError::BadLoadAddress {
+                addr,
+                mem_size: self.mem.len(),
+            });
+        }
+        let max = self.mem.len() - addr;
+        if rom.len() > max {
+            return Err(LoadError::TooLarge {
+                size: rom.len(),
+                max,
+            });
+        }
 
-        let end = START_ADRR + buffer.len();
-        self.mem[START_ADRR..end].copy_from_slice(&buffer);
+        self.mem[addr..addr + rom.len()].copy_from_slice(rom);
+        self.pc = addr as u16;
         Ok(())
     }
-}
-
-impl Jump {
-    pub fn new() -> Self {
-        let mut mask = [0u16; NBR_OPCODE];
-        let mut id = [0u16; NBR_OPCODE];
-
-        mask[0] = 0x0000;
-        id[0] = 0x0FFF; // 0NNN
-        mask[1] = 0xFFFF;
-        id[1] = 0x00E0; // 00E0
-        mask[2] = 0xFFFF;
-        id[2] = 0x00EE; // 00EE
-        mask[3] = 0xF000;
-        id[3] = 0x1000; // 1NNN
-        mask[4] = 0xF000;
-        id[4] = 0x2000; // 2NNN
-        mask[5] = 0xF000;
-        id[5] = 0x3000; // 3XNN
-        mask[6] = 0xF000;
-        id[6] = 0x4000; // 4XNN
-        mask[7] = 0xF00F;
-        id[7] = 0x5000; // 5XY0
-        mask[8] = 0xF000;
-        id[8] = 0x6000; // 6XNN
-        mask[9] = 0xF000;
-        id[9] = 0x7000; // 7XNN
-        mask[10] = 0xF00F;
-        id[10] = 0x8000; // 8XY0
-        mask[11] = 0xF00F;
-        id[11] = 0x8001; // 8XY1
-        mask[12] = 0xF00F;
-        id[12] = 0x8002; // 8XY2
-        mask[13] = 0xF00F;
-        id[13] = 0x8003; // BXY3
-        mask[14] = 0xF00F;
-        id[14] = 0x8004; // 8XY4
-        mask[15] = 0xF00F;
-        id[15] = 0x8005; // 8XY5
-        mask[16] = 0xF00F;
-        id[16] = 0x8006; // 8XY6
-        mask[17] = 0xF00F;
-        id[17] = 0x8007; // 8XY7
-        mask[18] = 0xF00F;
-        id[18] = 0x800E; // 8XYE
-        mask[19] = 0xF00F;
-        id[19] = 0x9000; // 9XY0
-        mask[20] = 0xF000;
-        id[20] = 0xA000; // ANNN
-        mask[21] = 0xF000;
-        id[21] = 0xB000; // BNNN
-        mask[22] = 0xF000;
-        id[22] = 0xC000; // CXNN
-        mask[23] = 0xF000;
-        id[23] = 0xD000; // DXYN
-        mask[24] = 0xF0FF;
-        id[24] = 0xE09E; // EX9E
-        mask[25] = 0xF0FF;
-        id[25] = 0xE0A1; // EXA1
-        mask[26] = 0xF0FF;
-        id[26] = 0xF007; // FX07
-        mask[27] = 0xF0FF;
-        id[27] = 0xF00A; // FX0A
-        mask[28] = 0xF0FF;
-        id[28] = 0xF015; // FX15
-        mask[29] = 0xF0FF;
-        id[29] = 0xF018; // FX18
-        mask[30] = 0xF0FF;
-        id[30] = 0xF01E; // FX1E
-        mask[31] = 0xF0FF;
-        id[31] = 0xF029; // FX29
-        mask[32] = 0xF0FF;
-        id[32] = 0xF033; // FX33
-        mask[33] = 0xF0FF;
-        id[33] = 0xF055; // FX55
-        mask[34] = 0xF0FF;
-        id[34] = 0xF065; // FX65
-
-        Self { mask, id }
-    }
 
-    pub fn get_action(&self, opcode: u16) -> u8 {
-        let mut action: u8 = 0;
-        while action < NBR_OPCODE as u8 {
-            let result = self.mask[action as usize] & opcode;
-
-            if result == self.id[action as usize] {
-                break;
-            }
-            action += 1;
-        }
-        return action;
+    pub fn load_game(&mut self, path: &str, addr: usize) -> Result<(), LoadError> {
+        let rom = read_rom(path)?;
+        self.load_rom(&rom, addr)
     }
 }
 
@@ -427,12 +512,22 @@ impl Keyboard {
         Keyboard {
             keys: [false; 16],
             awaiting_key: None,
+            keymap: Keymap::IDENTITY,
         }
     }
 
+    /// Change le remappage, les touches enfoncées sont relâchées.
+    pub fn set_keymap(&mut self, keymap: Keymap) {
+        self.keymap = keymap;
+        self.keys = [false; 16];
+    }
+
+    /// `key` est la touche du pavé, traduite par le remappage du jeu.
     pub fn set_key(&mut self, key: u8, pressed: bool) {
-        if key < 16 {
-            self.keys[key as usize] = pressed;
+        for (chip8_key, &pad_key) in self.keymap.keys.iter().enumerate() {
+            if pad_key == key {
+                self.keys[chip8_key] = pressed;
+            }
         }
     }
     pub fn ispressed(&self, key: u8) -> bool {
@@ -442,26 +537,23 @@ impl Keyboard {
             false
         }
     }
+}
 
-    pub fn map_sdl_key_to_chip8(keycode: sdl2::keyboard::Keycode) -> Option<u8> {
-        match keycode {
-            sdl2::keyboard::Keycode::Num1 => Some(0x1),
-            sdl2::keyboard::Keycode::Num2 => Some(0x2),
-            sdl2::keyboard::Keycode::Num3 => Some(0x3),
-            sdl2::keyboard::Keycode::Num4 => Some(0xC),
-            sdl2::keyboard::Keycode::Q => Some(0x4),
-            sdl2::keyboard::Keycode::W => Some(0x5),
-            sdl2::keyboard::Keycode::E => Some(0x6),
-            sdl2::keyboard::Keycode::R => Some(0xD),
-            sdl2::keyboard::Keycode::A => Some(0x7),
-            sdl2::keyboard::Keycode::S => Some(0x8),
-            sdl2::keyboard::Keycode::D => Some(0x9),
-            sdl2::keyboard::Keycode::F => Some(0xE),
-            sdl2::keyboard::Keycode::Z => Some(0xA),
-            sdl2::keyboard::Keycode::X => Some(0x0),
-            sdl2::keyboard::Keycode::C => Some(0xB),
-            sdl2::keyboard::Keycode::V => Some(0xF),
-            _ => None,
-        }
+impl Keymap {
+    /// Chaque touche du pavé déclenche la touche CHIP-8 du même nom.
+    pub const IDENTITY: Keymap = Keymap {
+        keys: [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15],
+    };
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        Self::IDENTITY
+    }
+}
+
+impl Default for Keyboard {
+    fn default() -> Self {
+        Self::new()
     }
 }
@@ -0,0 +1,179 @@
+use super::schema::{DecodeCache, Instruction};
+
+impl Instruction {
+    /// Décode un opcode en temps constant à partir de ses quartets.
+    pub fn decode(opcode: u16) -> Self {
+        let x = ((opcode & 0x0F00) >> 8) as u8;
+        let y = ((opcode & 0x00F0) >> 4) as u8;
+        let n = (opcode & 0x000F) as u8;
+        let nnn = opcode & 0x0FFF;
+        let kk = (opcode & 0x00FF) as u8;
+
+        match opcode >> 12 {
+            0x0 => match nnn {
+                0x0E0 => Instruction::Cls,
+                0x0EE => Instruction::Ret,
+                0x0FB => Instruction::ScrollRight,
+                0x0FC => Instruction::ScrollLeft,
+                0x0FD => Instruction::Exit,
+                0x0FE => Instruction::Lores,
+                0x0FF => Instruction::Hires,
+                _ if nnn & 0xFF0 == 0x0C0 => Instruction::ScrollDown { n },
+                _ if nnn & 0xFF0 == 0x0D0 => Instruction::ScrollUp { n },
+                _ => Instruction::Sys { nnn },
+            },
+            0x1 => Instruction::Jp { nnn },
+            0x2 => Instruction::Call { nnn },
+            0x3 => Instruction::SeImm { x, kk },
+            0x4 => Instruction::SneImm { x, kk },
+            0x5 => match n {
+                0x0 => Instruction::SeReg { x, y },
+                0x2 => Instruction::SaveRange { x, y },
+                0x3 => Instruction::LoadRange { x, y },
+                _ => Instruction::Unknown(opcode),
+            },
+            0x6 => Instruction::LdImm { x, kk },
+            0x7 => Instruction::AddImm { x, kk },
+            0x8 => match n {
+                0x0 => Instruction::LdReg { x, y },
+                0x1 => Instruction::Or { x, y },
+                0x2 => Instruction::And { x, y },
+                0x3 => Instruction::Xor { x, y },
+                0x4 => Instruction::AddReg { x, y },
+                0x5 => Instruction::Sub { x, y },
+                0x6 => Instruction::Shr { x, y },
+                0x7 => Instruction::Subn { x, y },
+                0xE => Instruction::Shl { x, y },
+                _ => Instruction::Unknown(opcode),
+            },
+            0x9 if n == 0 => Instruction::SneReg { x, y },
+            0xA => Instruction::LdI { nnn },
+            0xB => Instruction::JpV0 { x, nnn },
+            0xC => Instruction::Rnd { x, kk },
+            0xD => Instruction::Drw { x, y, n },
+            0xE => match kk {
+                0x9E => Instruction::Skp { x },
+                0xA1 => Instruction::Sknp { x },
+                _ => Instruction::Unknown(opcode),
+            },
+            0xF => match kk {
+                0x00 if x == 0 => Instruction::LdILong,
+                0x01 => Instruction::Plane { x },
+                0x02 if x == 0 => Instruction::Audio,
+                0x07 => Instruction::LdVxDt { x },
+                0x0A => Instruction::LdVxK { x },
+                0x15 => Instruction::LdDtVx { x },
+                0x18 => Instruction::LdStVx { x },
+                0x1E => Instruction::AddI { x },
+                0x29 => Instruction::LdF { x },
+                0x30 => Instruction::LdHf { x },
+                0x33 => Instruction::LdB { x },
+                0x3A => Instruction::Pitch { x },
+                0x55 => Instruction::LdIVx { x },
+                0x65 => Instruction::LdVxI { x },
+                0x75 => Instruction::LdRVx { x },
+                0x85 => Instruction::LdVxR { x },
+                _ => Instruction::Unknown(opcode),
+            },
+            _ => Instruction::Unknown(opcode),
+        }
+    }
+
+    /// Opcode correspondant à l'instruction, inverse de `decode`.
+    pub fn encode(self) -> u16 {
+        let xy = |base: u16, x: u8, y: u8, n: u16| base | (x as u16) << 8 | (y as u16) << 4 | n;
+        let xkk = |base: u16, x: u8, kk: u8| base | (x as u16) << 8 | kk as u16;
+
+        match self {
+            Instruction::Sys { nnn } => nnn & 0x0FFF,
+            Instruction::Cls => 0x00E0,
+            Instruction::Ret => 0x00EE,
+            Instruction::Jp { nnn } => 0x1000 | nnn & 0x0FFF,
+            Instruction::Call { nnn } => 0x2000 | nnn & 0x0FFF,
+            Instruction::SeImm { x, kk } => xkk(0x3000, x, kk),
+            Instruction::SneImm { x, kk } => xkk(0x4000, x, kk),
+            Instruction::SeReg { x, y } => xy(0x5000, x, y, 0x0),
+            Instruction::LdImm { x, kk } => xkk(0x6000, x, kk),
+            Instruction::AddImm { x, kk } => xkk(0x7000, x, kk),
+            Instruction::LdReg { x, y } => xy(0x8000, x, y, 0x0),
+            Instruction::Or { x, y } => xy(0x8000, x, y, 0x1),
+            Instruction::And { x, y } => xy(0x8000, x, y, 0x2),
+            Instruction::Xor { x, y } => xy(0x8000, x, y, 0x3),
+            Instruction::AddReg { x, y } => xy(0x8000, x, y, 0x4),
+            Instruction::Sub { x, y } => xy(0x8000, x, y, 0x5),
+            Instruction::Shr { x, y } => xy(0x8000, x, y, 0x6),
+            Instruction::Subn { x, y } => xy(0x8000, x, y, 0x7),
+            Instruction::Shl { x, y } => xy(0x8000, x, y, 0xE),
+            Instruction::SneReg { x, y } => xy(0x9000, x, y, 0x0),
+            Instruction::LdI { nnn } => 0xA000 | nnn & 0x0FFF,
+            // VX de BXNN fait partie de l'adresse
+            Instruction::JpV0 { nnn, .. } => 0xB000 | nnn & 0x0FFF,
+            Instruction::Rnd { x, kk } => xkk(0xC000, x, kk),
+            Instruction::Drw { x, y, n } => xy(0xD000, x, y, n as u16),
+            Instruction::Skp { x } => xkk(0xE000, x, 0x9E),
+            Instruction::Sknp { x } => xkk(0xE000, x, 0xA1),
+            Instruction::LdVxDt { x } => xkk(0xF000, x, 0x07),
+            Instruction::LdVxK { x } => xkk(0xF000, x, 0x0A),
+            Instruction::LdDtVx { x } => xkk(0xF000, x, 0x15),
+            Instruction::LdStVx { x } => xkk(0xF000, x, 0x18),
+            Instruction::AddI { x } => xkk(0xF000, x, 0x1E),
+            Instruction::LdF { x } => xkk(0xF000, x, 0x29),
+            Instruction::LdB { x } => xkk(0xF000, x, 0x33),
+            Instruction::LdIVx { x } => xkk(0xF000, x, 0x55),
+            Instruction::LdVxI { x } => xkk(0xF000, x, 0x65),
+            Instruction::ScrollDown { n } => 0x00C0 | n as u16,
+            Instruction::ScrollRight => 0x00FB,
+            Instruction::ScrollLeft => 0x00FC,
+            Instruction::Exit => 0x00FD,
+            Instruction::Lores => 0x00FE,
+            Instruction::Hires => 0x00FF,
+            Instruction::LdHf { x } => xkk(0xF000, x, 0x30),
+            Instruction::LdRVx { x } => xkk(0xF000, x, 0x75),
+            Instruction::LdVxR { x } => xkk(0xF000, x, 0x85),
+            Instruction::SaveRange { x, y } => xy(0x5000, x, y, 0x2),
+            Instruction::LoadRange { x, y } => xy(0x5000, x, y, 0x3),
+            Instruction::LdILong => 0xF000,
+            Instruction::Plane { x } => xkk(0xF000, x, 0x01),
+            Instruction::Audio => 0xF002,
+            Instruction::Pitch { x } => xkk(0xF000, x, 0x3A),
+            Instruction::ScrollUp { n } => 0x00D0 | n as u16,
+            Instruction::Unknown(opcode) => opcode,
+        }
+    }
+}
+
+impl DecodeCache {
+    pub fn new(mem_size: usize) -> Self {
+        DecodeCache {
+            entries: vec![None; mem_size],
+        }
+    }
+
+    /// Suit un changement de taille de la mémoire. Les entrées gardées n'ont pas besoin
+    /// d'être invalidées : `get` redécode celles dont l'opcode ne correspond plus.
+    pub fn resize(&mut self, mem_size: usize) {
+        self.entries.resize(mem_size, None);
+    }
+
+    /// Décode à l'avance chaque adresse de `mem[start..end]`, alignée ou non.
+    pub fn predecode(&mut self, mem: &[u8], start: usize, end: usize) {
+        for addr in start..end.min(mem.len() - 1) {
+            let opcode = ((mem[addr] as u16) << 8) | mem[addr + 1] as u16;
+            self.entries[addr] = Some((opcode, Instruction::decode(opcode)));
+        }
+    }
+
+    /// Retourne l'instruction en cache pour `addr`, ou la décode si l'opcode a changé.
+    pub fn get(&mut self, addr: usize, opcode: u16) -> Instruction {
+        match self.entries.get(addr) {
+            Some(Some((cached, instruction))) if *cached == opcode => *instruction,
+            _ => {
+                let instruction = Instruction::decode(opcode);
+                if let Some(entry) = self.entries.get_mut(addr) {
+                    *entry = Some((opcode, instruction));
+                }
+                instruction
+            }
+        }
+    }
+}
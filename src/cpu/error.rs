@@ -0,0 +1,58 @@
+use std::fmt;
+
+use super::schema::Chip8Error;
+
+impl Chip8Error {
+    pub fn pc(&self) -> u16 {
+        match *self {
+            Chip8Error::StackOverflow { pc, .. }
+            | Chip8Error::StackUnderflow { pc, .. }
+            | Chip8Error::UnknownOpcode { pc, .. }
+            | Chip8Error::MemoryOutOfBounds { pc, .. }
+            | Chip8Error::FetchOutOfBounds { pc, .. } => pc,
+        }
+    }
+
+    /// Opcode fautif, absent quand il n'a pas pu être lu en entier.
+    pub fn opcode(&self) -> Option<u16> {
+        match *self {
+            Chip8Error::StackOverflow { opcode, .. }
+            | Chip8Error::StackUnderflow { opcode, .. }
+            | Chip8Error::UnknownOpcode { opcode, .. }
+            | Chip8Error::MemoryOutOfBounds { opcode, .. } => Some(opcode),
+            Chip8Error::FetchOutOfBounds { .. } => None,
+        }
+    }
+}
+
+impl fmt::Display for Chip8Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Chip8Error::StackOverflow { .. } => write!(f, "stack overflow (more than 16 calls)")?,
+            Chip8Error::StackUnderflow { .. } => {
+                write!(f, "stack underflow (return without call)")?
+            }
+            Chip8Error::UnknownOpcode { .. } => write!(f, "unknown opcode")?,
+            Chip8Error::MemoryOutOfBounds { addr, .. } => {
+                write!(f, "memory access out of bounds (address {:#06X})", addr)?
+            }
+            Chip8Error::FetchOutOfBounds { .. } => write!(f, "instruction fetch out of bounds")?,
+        }
+        write!(f, " at PC={:#05X}", self.pc())?;
+        match (self.opcode(), self) {
+            (Some(opcode), _) => write!(f, " (opcode {:04X})", opcode),
+            (
+                None,
+                Chip8Error::FetchOutOfBounds {
+                    partial: Some(byte),
+                    ..
+                },
+            ) => {
+                write!(f, " (partial opcode {:02X}..)", byte)
+            }
+            (None, _) => Ok(()),
+        }
+    }
+}
+
+impl std::error::Error for Chip8Error {}
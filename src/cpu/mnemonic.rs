@@ -0,0 +1,66 @@
+use std::fmt;
+
+use super::schema::Instruction;
+
+/// Mnémoniques au format CHIPPER (`LD V0, #0A`, `DRW V0, V1, 4`), adresses en `#NNN`.
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Instruction::Sys { nnn } => write!(f, "SYS #{:03X}", nnn),
+            Instruction::Cls => write!(f, "CLS"),
+            Instruction::Ret => write!(f, "RET"),
+            Instruction::Jp { nnn } => write!(f, "JP #{:03X}", nnn),
+            Instruction::Call { nnn } => write!(f, "CALL #{:03X}", nnn),
+            Instruction::SeImm { x, kk } => write!(f, "SE V{:X}, #{:02X}", x, kk),
+            Instruction::SneImm { x, kk } => write!(f, "SNE V{:X}, #{:02X}", x, kk),
+            Instruction::SeReg { x, y } => write!(f, "SE V{:X}, V{:X}", x, y),
+            Instruction::LdImm { x, kk } => write!(f, "LD V{:X}, #{:02X}", x, kk),
+            Instruction::AddImm { x, kk } => write!(f, "ADD V{:X}, #{:02X}", x, kk),
+            Instruction::LdReg { x, y } => write!(f, "LD V{:X}, V{:X}", x, y),
+            Instruction::Or { x, y } => write!(f, "OR V{:X}, V{:X}", x, y),
+            Instruction::And { x, y } => write!(f, "AND V{:X}, V{:X}", x, y),
+            Instruction::Xor { x, y } => write!(f, "XOR V{:X}, V{:X}", x, y),
+            Instruction::AddReg { x, y } => write!(f, "ADD V{:X}, V{:X}", x, y),
+            Instruction::Sub { x, y } => write!(f, "SUB V{:X}, V{:X}", x, y),
+            // CHIPPER assemble `SHR VX` en 8X06, VY n'est écrit que s'il est utilisé
+            Instruction::Shr { x, y: 0 } => write!(f, "SHR V{:X}", x),
+            Instruction::Shr { x, y } => write!(f, "SHR V{:X}, V{:X}", x, y),
+            Instruction::Subn { x, y } => write!(f, "SUBN V{:X}, V{:X}", x, y),
+            Instruction::Shl { x, y: 0 } => write!(f, "SHL V{:X}", x),
+            Instruction::Shl { x, y } => write!(f, "SHL V{:X}, V{:X}", x, y),
+            Instruction::SneReg { x, y } => write!(f, "SNE V{:X}, V{:X}", x, y),
+            Instruction::LdI { nnn } => write!(f, "LD I, #{:03X}", nnn),
+            Instruction::JpV0 { nnn, .. } => write!(f, "JP V0, #{:03X}", nnn),
+            Instruction::Rnd { x, kk } => write!(f, "RND V{:X}, #{:02X}", x, kk),
+            Instruction::Drw { x, y, n } => write!(f, "DRW V{:X}, V{:X}, {}", x, y, n),
+            Instruction::Skp { x } => write!(f, "SKP V{:X}", x),
+            Instruction::Sknp { x } => write!(f, "SKNP V{:X}", x),
+            Instruction::LdVxDt { x } => write!(f, "LD V{:X}, DT", x),
+            Instruction::LdVxK { x } => write!(f, "LD V{:X}, K", x),
+            Instruction::LdDtVx { x } => write!(f, "LD DT, V{:X}", x),
+            Instruction::LdStVx { x } => write!(f, "LD ST, V{:X}", x),
+            Instruction::AddI { x } => write!(f, "ADD I, V{:X}", x),
+            Instruction::LdF { x } => write!(f, "LD F, V{:X}", x),
+            Instruction::LdB { x } => write!(f, "LD B, V{:X}", x),
+            Instruction::LdIVx { x } => write!(f, "LD [I], V{:X}", x),
+            Instruction::LdVxI { x } => write!(f, "LD V{:X}, [I]", x),
+            Instruction::ScrollDown { n } => write!(f, "SCD {}", n),
+            Instruction::ScrollRight => write!(f, "SCR"),
+            Instruction::ScrollLeft => write!(f, "SCL"),
+            Instruction::Exit => write!(f, "EXIT"),
+            Instruction::Lores => write!(f, "LOW"),
+            Instruction::Hires => write!(f, "HIGH"),
+            Instruction::LdHf { x } => write!(f, "LD HF, V{:X}", x),
+            Instruction::LdRVx { x } => write!(f, "LD R, V{:X}", x),
+            Instruction::LdVxR { x } => write!(f, "LD V{:X}, R", x),
+            Instruction::SaveRange { x, y } => write!(f, "SAVE V{:X}, V{:X}", x, y),
+            Instruction::LoadRange { x, y } => write!(f, "LOAD V{:X}, V{:X}", x, y),
+            Instruction::LdILong => write!(f, "LD I, LONG"),
+            Instruction::Plane { x } => write!(f, "PLANE {}", x),
+            Instruction::Audio => write!(f, "AUDIO"),
+            Instruction::Pitch { x } => write!(f, "PITCH V{:X}", x),
+            Instruction::ScrollUp { n } => write!(f, "SCU {}", n),
+            Instruction::Unknown(opcode) => write!(f, "DW #{:04X}", opcode),
+        }
+    }
+}
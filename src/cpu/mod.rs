@@ -1,2 +1,8 @@
+#[allow(clippy::module_inception)]
 pub mod cpu;
+pub mod decode;
+pub mod error;
+pub mod mnemonic;
+pub mod quirks;
+pub mod random;
 pub mod schema;
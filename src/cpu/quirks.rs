@@ -0,0 +1,133 @@
+use std::str::FromStr;
+
+use super::schema::{Instruction, Profile, Quirks, MEM_SIZE, XO_MEM_SIZE};
+
+impl Quirks {
+    /// Comportement de l'interpréteur d'origine du COSMAC VIP.
+    pub const COSMAC_VIP: Quirks = Quirks {
+        shift_uses_vy: true,
+        load_store_increments_i: true,
+        jump_uses_vx: false,
+        logic_resets_vf: true,
+        clip_sprites: true,
+        superchip_opcodes: false,
+        xochip_opcodes: false,
+    };
+
+    /// CHIP-48 sur HP-48.
+    pub const CHIP48: Quirks = Quirks {
+        shift_uses_vy: false,
+        load_store_increments_i: false,
+        jump_uses_vx: true,
+        logic_resets_vf: false,
+        clip_sprites: true,
+        superchip_opcodes: false,
+        xochip_opcodes: false,
+    };
+
+    /// SUPER-CHIP 1.1.
+    pub const SUPER_CHIP: Quirks = Quirks {
+        shift_uses_vy: false,
+        load_store_increments_i: false,
+        jump_uses_vx: true,
+        logic_resets_vf: false,
+        clip_sprites: true,
+        superchip_opcodes: true,
+        xochip_opcodes: false,
+    };
+
+    /// XO-CHIP (Octo).
+    pub const XO_CHIP: Quirks = Quirks {
+        shift_uses_vy: true,
+        load_store_increments_i: true,
+        jump_uses_vx: false,
+        logic_resets_vf: false,
+        clip_sprites: false,
+        superchip_opcodes: true,
+        xochip_opcodes: true,
+    };
+
+    /// Comportement historique de cet émulateur, attendu par la plupart des ROMs récentes.
+    pub const MODERN: Quirks = Quirks {
+        shift_uses_vy: false,
+        load_store_increments_i: false,
+        jump_uses_vx: false,
+        logic_resets_vf: false,
+        clip_sprites: false,
+        superchip_opcodes: true,
+        xochip_opcodes: true,
+    };
+
+    /// Faux pour une instruction inconnue ou une extension absente de l'interpréteur émulé.
+    pub fn supports(self, instruction: Instruction) -> bool {
+        match instruction {
+            Instruction::Unknown(_) => false,
+            Instruction::ScrollDown { .. }
+            | Instruction::ScrollRight
+            | Instruction::ScrollLeft
+            | Instruction::Exit
+            | Instruction::Lores
+            | Instruction::Hires
+            | Instruction::LdHf { .. }
+            | Instruction::LdRVx { .. }
+            | Instruction::LdVxR { .. } => self.superchip_opcodes,
+            Instruction::SaveRange { .. }
+            | Instruction::LoadRange { .. }
+            | Instruction::LdILong
+            | Instruction::Plane { .. }
+            | Instruction::Audio
+            | Instruction::Pitch { .. }
+            | Instruction::ScrollUp { .. } => self.xochip_opcodes,
+            _ => true,
+        }
+    }
+}
+
+impl Default for Quirks {
+    fn default() -> Self {
+        Quirks::MODERN
+    }
+}
+
+impl Profile {
+    /// Taille de la mémoire adressable pour ce profil.
+    pub fn mem_size(self) -> usize {
+        match self {
+            Profile::XoChip => XO_MEM_SIZE,
+            _ => MEM_SIZE,
+        }
+    }
+
+    pub fn quirks(self) -> Quirks {
+        match self {
+            Profile::CosmacVip => Quirks::COSMAC_VIP,
+            Profile::Chip48 => Quirks::CHIP48,
+            Profile::SuperChip => Quirks::SUPER_CHIP,
+            Profile::XoChip => Quirks::XO_CHIP,
+            Profile::Modern => Quirks::MODERN,
+        }
+    }
+
+    /// Vrai si l'interpréteur de ce profil connaît l'instruction.
+    pub fn supports(self, instruction: Instruction) -> bool {
+        self.quirks().supports(instruction)
+    }
+}
+
+impl FromStr for Profile {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "vip" | "cosmac-vip" | "chip8" | "chip-8" => Ok(Profile::CosmacVip),
+            "chip48" | "chip-48" => Ok(Profile::Chip48),
+            "schip" | "superchip" | "super-chip" => Ok(Profile::SuperChip),
+            "xochip" | "xo-chip" => Ok(Profile::XoChip),
+            "modern" => Ok(Profile::Modern),
+            _ => Err(format!(
+                "unknown profile '{}' (expected vip, chip48, schip, xochip or modern)",
+                s
+            )),
+        }
+    }
+}
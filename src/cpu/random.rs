@@ -0,0 +1,195 @@
+use std::{fmt, str::FromStr};
+
+use super::schema::{
+    FixedSequence, FontTableRandom, OsRandom, RandomSource, RngKind, RngState, XorShift,
+    CHIP8_FONTSET, SCHIP_FONTSET,
+};
+
+impl RandomSource for OsRandom {
+    fn next_byte(&mut self) -> u8 {
+        rand::random()
+    }
+
+    fn box_clone(&self) -> Box<dyn RandomSource> {
+        Box::new(*self)
+    }
+}
+
+impl XorShift {
+    pub fn new(seed: u64) -> Self {
+        // un état nul resterait nul, la graine 0 est donc décalée
+        XorShift {
+            state: if seed == 0 {
+                0x9E37_79B9_7F4A_7C15
+            } else {
+                seed
+            },
+        }
+    }
+}
+
+impl RandomSource for XorShift {
+    fn next_byte(&mut self) -> u8 {
+        self.state ^= self.state >> 12;
+        self.state ^= self.state << 25;
+        self.state ^= self.state >> 27;
+        // les bits de poids fort du produit sont les mieux mélangés
+        (self.state.wrapping_mul(0x2545_F491_4F6C_DD1D) >> 56) as u8
+    }
+
+    fn box_clone(&self) -> Box<dyn RandomSource> {
+        Box::new(*self)
+    }
+
+    fn snapshot(&self) -> Option<RngState> {
+        Some(RngState::XorShift(self.state))
+    }
+}
+
+impl FontTableRandom {
+    /// Les deux octets de la graine donnent la position du pointeur et le premier tirage.
+    pub fn new(seed: u64) -> Self {
+        let mut page = [0u8; 256];
+        let fonts = CHIP8_FONTSET.iter().chain(SCHIP_FONTSET.iter());
+        for (byte, &font) in page.iter_mut().zip(fonts) {
+            *byte = font;
+        }
+        FontTableRandom {
+            pointer: seed as u8,
+            value: (seed >> 8) as u8,
+            page,
+        }
+    }
+}
+
+impl RandomSource for FontTableRandom {
+    fn next_byte(&mut self) -> u8 {
+        self.pointer = self.pointer.wrapping_add(1);
+        self.value = self.value.wrapping_add(self.page[self.pointer as usize]);
+        self.value
+    }
+
+    fn box_clone(&self) -> Box<dyn RandomSource> {
+        Box::new(*self)
+    }
+
+    fn snapshot(&self) -> Option<RngState> {
+        Some(RngState::FontTable {
+            pointer: self.pointer,
+            value: self.value,
+        })
+    }
+}
+
+impl FixedSequence {
+    pub fn new(values: Vec<u8>) -> Self {
+        FixedSequence { values, pos: 0 }
+    }
+}
+
+impl RandomSource for FixedSequence {
+    fn next_byte(&mut self) -> u8 {
+        let Some(&value) = self.values.get(self.pos) else {
+            return 0;
+        };
+        self.pos = (self.pos + 1) % self.values.len();
+        value
+    }
+
+    fn box_clone(&self) -> Box<dyn RandomSource> {
+        Box::new(self.clone())
+    }
+
+    fn snapshot(&self) -> Option<RngState> {
+        Some(RngState::Sequence {
+            values: self.values.clone(),
+            pos: self.pos,
+        })
+    }
+}
+
+impl Clone for Box<dyn RandomSource> {
+    fn clone(&self) -> Self {
+        self.box_clone()
+    }
+}
+
+impl RngKind {
+    /// Source neuve de ce type, partant de `seed` (ignorée par `Os`).
+    pub fn source(&self, seed: u64) -> Box<dyn RandomSource> {
+        match self {
+            RngKind::Os => Box::new(OsRandom),
+            RngKind::XorShift => Box::new(XorShift::new(seed)),
+            RngKind::FontTable => Box::new(FontTableRandom::new(seed)),
+            RngKind::Sequence(values) => Box::new(FixedSequence::new(values.clone())),
+        }
+    }
+
+    /// Type utilisé quand les tirages doivent se rejouer : l'entropie du système
+    /// est remplacée par xorshift.
+    pub fn replayable(&self) -> RngKind {
+        match self {
+            RngKind::Os => RngKind::XorShift,
+            other => other.clone(),
+        }
+    }
+}
+
+impl RngState {
+    /// Générateur repris exactement à cet état.
+    pub fn source(&self) -> Box<dyn RandomSource> {
+        match self {
+            RngState::XorShift(state) => Box::new(XorShift { state: *state }),
+            RngState::FontTable { pointer, value } => Box::new(FontTableRandom {
+                pointer: *pointer,
+                value: *value,
+                ..FontTableRandom::new(0)
+            }),
+            RngState::Sequence { values, pos } => Box::new(FixedSequence {
+                values: values.clone(),
+                pos: *pos,
+            }),
+        }
+    }
+}
+
+impl FromStr for RngKind {
+    type Err = String;
+
+    /// Accepte `os`, `xorshift`, `fonttable` ou `seq:` suivi d'octets hexadécimaux (`seq:00,FF,3C`).
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Some(values) = s.strip_prefix("seq:") {
+            return values
+                .split(',')
+                .map(|v| {
+                    u8::from_str_radix(v.trim().trim_start_matches("0x"), 16)
+                        .map_err(|e| format!("invalid byte '{}' in sequence: {}", v, e))
+                })
+                .collect::<Result<_, _>>()
+                .map(RngKind::Sequence);
+        }
+        match s.to_ascii_lowercase().as_str() {
+            "os" => Ok(RngKind::Os),
+            "xorshift" => Ok(RngKind::XorShift),
+            "fonttable" => Ok(RngKind::FontTable),
+            _ => Err(format!(
+                "unknown random source '{}', expected os, xorshift, fonttable or seq:XX,YY,...",
+                s
+            )),
+        }
+    }
+}
+
+impl fmt::Display for RngKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RngKind::Os => write!(f, "os"),
+            RngKind::XorShift => write!(f, "xorshift"),
+            RngKind::FontTable => write!(f, "fonttable"),
+            RngKind::Sequence(values) => {
+                let bytes: Vec<String> = values.iter().map(|v| format!("{:02X}", v)).collect();
+                write!(f, "seq:{}", bytes.join(","))
+            }
+        }
+    }
+}
@@ -1,6 +1,7 @@
 pub const MEM_SIZE: usize = 4096;
+pub const XO_MEM_SIZE: usize = 65536; // mémoire étendue XO-CHIP
 pub const START_ADRR: usize = 0x200;
-pub const NBR_OPCODE: usize = 35;
+pub const DEFAULT_PITCH: u8 = 64; // FX3A, 64 correspond à 4000 Hz
 pub const CPU_SPEED: usize = 10;
 
 pub const CHIP8_FONTSET: [u8; 80] = [
@@ -22,25 +23,227 @@ pub const CHIP8_FONTSET: [u8; 80] = [
     0xF0, 0x80, 0xF0, 0x80, 0x80, // F
 ];
 
-#[allow(non_snake_case)]
+pub const BIG_FONT_ADDR: usize = 0x50; // la grande police SUPER-CHIP suit la police 4x5
+
+pub const SCHIP_FONTSET: [u8; 160] = [
+    0x3C, 0x7E, 0xE7, 0xC3, 0xC3, 0xC3, 0xC3, 0xE7, 0x7E, 0x3C, // 0
+    0x18, 0x38, 0x58, 0x18, 0x18, 0x18, 0x18, 0x18, 0x18, 0x3C, // 1
+    0x3E, 0x7F, 0xC3, 0x06, 0x0C, 0x18, 0x30, 0x60, 0xFF, 0xFF, // 2
+    0x3C, 0x7E, 0xC3, 0x03, 0x0E, 0x0E, 0x03, 0xC3, 0x7E, 0x3C, // 3
+    0x06, 0x0E, 0x1E, 0x36, 0x66, 0xC6, 0xFF, 0xFF, 0x06, 0x06, // 4
+    0xFF, 0xFF, 0xC0, 0xC0, 0xFC, 0xFE, 0x03, 0xC3, 0x7E, 0x3C, // 5
+    0x3E, 0x7C, 0xE0, 0xC0, 0xFC, 0xFE, 0xC3, 0xC3, 0x7E, 0x3C, // 6
+    0xFF, 0xFF, 0x03, 0x06, 0x0C, 0x18, 0x30, 0x60, 0x60, 0x60, // 7
+    0x3C, 0x7E, 0xC3, 0xC3, 0x7E, 0x7E, 0xC3, 0xC3, 0x7E, 0x3C, // 8
+    0x3C, 0x7E, 0xC3, 0xC3, 0x7F, 0x3F, 0x03, 0x03, 0x3E, 0x7C, // 9
+    0x18, 0x3C, 0x66, 0xC3, 0xC3, 0xFF, 0xFF, 0xC3, 0xC3, 0xC3, // A
+    0xFC, 0xFE, 0xC3, 0xC3, 0xFE, 0xFE, 0xC3, 0xC3, 0xFE, 0xFC, // B
+    0x3C, 0x7E, 0xC3, 0xC0, 0xC0, 0xC0, 0xC0, 0xC3, 0x7E, 0x3C, // C
+    0xFC, 0xFE, 0xC3, 0xC3, 0xC3, 0xC3, 0xC3, 0xC3, 0xFE, 0xFC, // D
+    0xFF, 0xFF, 0xC0, 0xC0, 0xFF, 0xFF, 0xC0, 0xC0, 0xFF, 0xFF, // E
+    0xFF, 0xFF, 0xC0, 0xC0, 0xFF, 0xFF, 0xC0, 0xC0, 0xC0, 0xC0, // F
+];
+
+#[allow(non_snake_case, clippy::upper_case_acronyms)]
+#[derive(Clone)]
 pub struct CPU {
-    pub mem: [u8; MEM_SIZE], // memoire
-    pub V: [u8; 16],         // le registre
-    pub I: u16,              // stock une adresse mémoire ou dessinateur
-    pub stack: [u16; 16],    // pour gérer les sauts dans « mémoire », 16 au maximum
+    pub mem: Vec<u8>,     // memoire, MEM_SIZE ou XO_MEM_SIZE octets
+    pub V: [u8; 16],      // le registre
+    pub I: u16,           // stock une adresse mémoire ou dessinateur
+    pub stack: [u16; 16], // pour gérer les sauts dans « mémoire », 16 au maximum
     pub sp: u8, // (stack pointer) stock le nombre de sauts effectués pour ne pas dépasser 16
     pub game_count: u8, // compteur pour la synchronisation
     pub sound_count: u8, // compteur pour le son
     pub pc: u16, // pour parcourir le tableau « mémoire »
     pub debug: bool,
+    pub quirks: Quirks,
+    pub rpl: [u8; 16],                   // drapeaux utilisateur RPL (FX75/FX85)
+    pub exited: bool,                    // le programme a exécuté 00FD
+    pub audio_pattern: Option<[u8; 16]>, // motif sonore 1 bit XO-CHIP (F002), bip sinon
+    pub pitch: u8,                       // hauteur du motif sonore (FX3A)
+    pub rng: Box<dyn RandomSource>,      // source des tirages de CXNN
+}
+
+/// Source des octets tirés par CXNN, avant le masque NN.
+pub trait RandomSource {
+    fn next_byte(&mut self) -> u8;
+    /// Copie de la source dans son état courant, pour que le CPU reste clonable.
+    fn box_clone(&self) -> Box<dyn RandomSource>;
+    /// État à enregistrer dans les sauvegardes, `None` pour une source qui ne se rejoue pas.
+    fn snapshot(&self) -> Option<RngState> {
+        None
+    }
+}
+
+/// État d'un générateur rejouable, gardé par les sauvegardes d'état pour que les
+/// tirages reprennent au même point après un chargement ou un retour en arrière.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RngState {
+    XorShift(u64),
+    FontTable { pointer: u8, value: u8 },
+    Sequence { values: Vec<u8>, pos: usize },
+}
+
+/// Entropie du système, comme `rand::random` : les tirages ne se rejouent pas.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct OsRandom;
+
+/// xorshift64* : rapide, et toujours la même suite pour une même graine.
+#[derive(Clone, Copy, Debug)]
+pub struct XorShift {
+    pub state: u64, // jamais nul
+}
+
+/// Somme glissante sur la table des polices : un pointeur parcourt une page de 256 octets
+/// formée des polices CHIP-8 et SUPER-CHIP, et l'octet lu est ajouté au tirage précédent.
+/// Même principe que la routine de la COSMAC VIP, mais ce n'est pas elle : la VIP lit
+/// le code de son interpréteur, qui ne fait pas partie de l'émulateur.
+#[derive(Clone, Copy, Debug)]
+pub struct FontTableRandom {
+    pub pointer: u8,
+    pub value: u8,
+    pub page: [u8; 256],
+}
+
+/// Suite imposée, reprise au début une fois épuisée ; pour les tests et les ROMs de test.
+#[derive(Clone, Debug, Default)]
+pub struct FixedSequence {
+    pub values: Vec<u8>,
+    pub pos: usize,
+}
+
+/// Générateur choisi par `--rng`. Tous sauf `Os` redonnent les mêmes tirages
+/// pour une même graine.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RngKind {
+    Os,
+    XorShift,
+    FontTable,
+    Sequence(Vec<u8>),
 }
 
-pub struct Jump {
-    pub mask: [u16; NBR_OPCODE],
-    pub id: [u16; NBR_OPCODE],
+/// Instruction décodée, partagée par l'interpréteur, le désassembleur et le débogueur.
+/// `x` et `y` sont des numéros de registre, `kk` un octet immédiat, `n` un quartet,
+/// `nnn` une adresse sur 12 bits.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Instruction {
+    Sys { nnn: u16 },            // 0NNN
+    Cls,                         // 00E0
+    Ret,                         // 00EE
+    Jp { nnn: u16 },             // 1NNN
+    Call { nnn: u16 },           // 2NNN
+    SeImm { x: u8, kk: u8 },     // 3XKK
+    SneImm { x: u8, kk: u8 },    // 4XKK
+    SeReg { x: u8, y: u8 },      // 5XY0
+    LdImm { x: u8, kk: u8 },     // 6XKK
+    AddImm { x: u8, kk: u8 },    // 7XKK
+    LdReg { x: u8, y: u8 },      // 8XY0
+    Or { x: u8, y: u8 },         // 8XY1
+    And { x: u8, y: u8 },        // 8XY2
+    Xor { x: u8, y: u8 },        // 8XY3
+    AddReg { x: u8, y: u8 },     // 8XY4
+    Sub { x: u8, y: u8 },        // 8XY5
+    Shr { x: u8, y: u8 },        // 8XY6
+    Subn { x: u8, y: u8 },       // 8XY7
+    Shl { x: u8, y: u8 },        // 8XYE
+    SneReg { x: u8, y: u8 },     // 9XY0
+    LdI { nnn: u16 },            // ANNN
+    JpV0 { x: u8, nnn: u16 },    // BNNN
+    Rnd { x: u8, kk: u8 },       // CXKK
+    Drw { x: u8, y: u8, n: u8 }, // DXYN
+    Skp { x: u8 },               // EX9E
+    Sknp { x: u8 },              // EXA1
+    LdVxDt { x: u8 },            // FX07
+    LdVxK { x: u8 },             // FX0A
+    LdDtVx { x: u8 },            // FX15
+    LdStVx { x: u8 },            // FX18
+    AddI { x: u8 },              // FX1E
+    LdF { x: u8 },               // FX29
+    LdB { x: u8 },               // FX33
+    LdIVx { x: u8 },             // FX55
+    LdVxI { x: u8 },             // FX65
+    // SUPER-CHIP
+    ScrollDown { n: u8 }, // 00CN
+    ScrollRight,          // 00FB
+    ScrollLeft,           // 00FC
+    Exit,                 // 00FD
+    Lores,                // 00FE
+    Hires,                // 00FF
+    LdHf { x: u8 },       // FX30
+    LdRVx { x: u8 },      // FX75
+    LdVxR { x: u8 },      // FX85
+    // XO-CHIP
+    SaveRange { x: u8, y: u8 }, // 5XY2
+    LoadRange { x: u8, y: u8 }, // 5XY3
+    LdILong,                    // F000 NNNN, l'adresse suit l'opcode
+    Plane { x: u8 },            // FN01
+    Audio,                      // F002
+    Pitch { x: u8 },            // FX3A
+    ScrollUp { n: u8 },         // 00DN
+    Unknown(u16),
+}
+
+/// Cache des instructions déjà décodées, indexé par adresse. L'opcode est gardé avec
+/// l'instruction, un code auto-modifiant est donc simplement redécodé.
+pub struct DecodeCache {
+    pub entries: Vec<Option<(u16, Instruction)>>,
 }
 
 pub struct Keyboard {
     pub keys: [bool; 16],
     pub awaiting_key: Option<u8>,
+    pub keymap: Keymap,
+}
+
+/// Remappage propre à un jeu (fichier .c8k) : la touche CHIP-8 `i` est déclenchée
+/// par la touche du pavé `keys[i]`, une touche du pavé peut en déclencher plusieurs.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Keymap {
+    pub keys: [u8; 16],
+}
+
+/// Variations de comportement entre les interpréteurs CHIP-8 historiques.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Quirks {
+    /// 8XY6/8XYE décalent VY dans VX (COSMAC VIP) au lieu de décaler VX sur place.
+    pub shift_uses_vy: bool,
+    /// FX55/FX65 laissent I à I + X + 1 après l'accès mémoire.
+    pub load_store_increments_i: bool,
+    /// BNNN se comporte en BXNN : saut à XNN + VX au lieu de NNN + V0.
+    pub jump_uses_vx: bool,
+    /// 8XY1/8XY2/8XY3 remettent VF à 0.
+    pub logic_resets_vf: bool,
+    /// DXYN coupe les sprites au bord de l'écran au lieu de les faire reboucler.
+    pub clip_sprites: bool,
+    /// Les instructions SUPER-CHIP (00CN, 00FB-00FF, FX30, FX75/FX85) sont reconnues.
+    pub superchip_opcodes: bool,
+    /// Les instructions XO-CHIP (5XY2/5XY3, F000 NNNN, FN01, F002, FX3A, 00DN) sont reconnues.
+    pub xochip_opcodes: bool,
+}
+
+/// Préréglages de quirks correspondant aux interpréteurs connus.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Profile {
+    CosmacVip,
+    Chip48,
+    SuperChip,
+    XoChip,
+    Modern,
+}
+
+/// Faute levée par une instruction. `pc` désigne l'instruction fautive, `opcode()` son
+/// opcode quand il a pu être lu.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Chip8Error {
+    /// 2NNN avec déjà 16 appels en cours.
+    StackOverflow { pc: u16, opcode: u16 },
+    /// 00EE sans appel en cours.
+    StackUnderflow { pc: u16, opcode: u16 },
+    /// Opcode qui ne correspond à aucune instruction.
+    UnknownOpcode { pc: u16, opcode: u16 },
+    /// Accès mémoire hors de la mémoire adressable, `addr` est la première adresse invalide.
+    MemoryOutOfBounds { pc: u16, opcode: u16, addr: usize },
+    /// PC sur le dernier octet de la mémoire ou au-delà : l'opcode n'a pas pu être lu en
+    /// entier, `partial` est son octet de poids fort s'il existe.
+    FetchOutOfBounds { pc: u16, partial: Option<u8> },
 }
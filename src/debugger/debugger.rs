@@ -0,0 +1,280 @@
+use std::{fmt::Write, str::FromStr};
+
+use crate::cpu::schema::Instruction;
+use crate::machine::schema::Machine;
+
+use super::schema::{Action, Command, Debugger, Snapshot, StepTarget};
+
+const HELP: &str = "\
+c, continue        resume execution
+s, step [N]        execute N instructions (default 1, also on empty line)
+n, next            step over a CALL
+f, finish          run until the current subroutine returns
+b, break ADDR      set a breakpoint at ADDR (hex)
+d, delete ADDR     remove the breakpoint at ADDR
+bl, breakpoints    list breakpoints
+r, regs            show registers and disassembly
+x ADDR [LEN]       dump LEN bytes of memory from ADDR (hex, default 16)
+w ADDR[-END] [r|w|rw] [log]
+                   watch memory writes (default), reads or both
+w REG [log]        watch a register (V0-VF, I) for changes
+w REG OP VALUE [log]
+                   break when a condition becomes true (==, !=, <, <=, >, >=)
+wl, watchpoints    list watchpoints
+wd N               remove watchpoint N
+q, quit            stop the emulator";
+
+impl Debugger {
+    pub fn new(paused: bool) -> Self {
+        Debugger {
+            paused,
+            ..Default::default()
+        }
+    }
+
+    /// Appelé avant chaque instruction : vrai si l'exécution doit s'arrêter sur `pc`.
+    pub fn should_break(&mut self, machine: &Machine) -> bool {
+        let (pc, sp) = (machine.cpu.pc, machine.cpu.sp);
+        let resuming = self.resume_from.take() == Some(pc);
+
+        let reached = match self.target {
+            Some(StepTarget::Over { pc: ret, sp: depth }) => pc == ret && sp == depth,
+            Some(StepTarget::Out { sp: depth }) => sp < depth,
+            None => false,
+        };
+
+        let watched = self.check_watchpoints(machine, resuming);
+
+        if reached || watched || (!resuming && self.breakpoints.contains(&pc)) {
+            self.paused = true;
+            self.target = None;
+            return true;
+        }
+        false
+    }
+
+    /// Exécute une commande et retourne l'action à suivre avec le texte à afficher.
+    pub fn execute(&mut self, command: Command, machine: &mut Machine) -> (Action, String) {
+        match command {
+            Command::Continue => (self.resume(machine), String::new()),
+            Command::Step(count) => {
+                // l'instruction courante a déjà été vérifiée à l'arrêt, on la lance directement
+                self.previous = Some(Snapshot::of(&machine.cpu));
+                for _ in 0..count {
+                    if let Err(e) = machine.run_instruction() {
+                        return (Action::Paused, format!("{}\n{}", e, Self::state(machine)));
+                    }
+                    // registres changés par cette instruction, accès mémoire de la suivante
+                    if self.check_watchpoints(machine, false) {
+                        break;
+                    }
+                }
+                (Action::Paused, Self::state(machine))
+            }
+            Command::Next => {
+                let opcode = machine.cpu.check_fetch().map(|_| machine.cpu.get_opcode());
+                match opcode.map(Instruction::decode) {
+                    Ok(Instruction::Call { .. }) => {
+                        self.target = Some(StepTarget::Over {
+                            pc: machine.cpu.pc.wrapping_add(2),
+                            sp: machine.cpu.sp,
+                        });
+                        (self.resume(machine), String::new())
+                    }
+                    _ => self.execute(Command::Step(1), machine),
+                }
+            }
+            Command::Finish => {
+                if machine.cpu.sp == 0 {
+                    return (Action::Paused, "not inside a subroutine".to_string());
+                }
+                self.target = Some(StepTarget::Out { sp: machine.cpu.sp });
+                (self.resume(machine), String::new())
+            }
+            Command::Break(addr) => {
+                self.breakpoints.insert(addr);
+                (Action::Paused, format!("breakpoint set at #{:03X}", addr))
+            }
+            Command::Delete(addr) => {
+                let msg = if self.breakpoints.remove(&addr) {
+                    format!("breakpoint at #{:03X} removed", addr)
+                } else {
+                    format!("no breakpoint at #{:03X}", addr)
+                };
+                (Action::Paused, msg)
+            }
+            Command::Breakpoints => {
+                let list: Vec<String> = self
+                    .breakpoints
+                    .iter()
+                    .map(|a| format!("#{:03X}", a))
+                    .collect();
+                let msg = if list.is_empty() {
+                    "no breakpoints".to_string()
+                } else {
+                    list.join(" ")
+                };
+                (Action::Paused, msg)
+            }
+            Command::Regs => (Action::Paused, Self::state(machine)),
+            Command::Mem { addr, len } => (Action::Paused, Self::dump_mem(machine, addr, len)),
+            Command::Watch(watch) => {
+                let index = self.add_watch(watch, machine);
+                (Action::Paused, format!("watchpoint {}: {}", index, watch))
+            }
+            Command::Unwatch(index) => {
+                let msg = if index < self.watchpoints.len() {
+                    format!(
+                        "watchpoint {} removed: {}",
+                        index,
+                        self.watchpoints.remove(index)
+                    )
+                } else {
+                    format!("no watchpoint {}", index)
+                };
+                (Action::Paused, msg)
+            }
+            Command::Watchpoints => {
+                let list: Vec<String> = self
+                    .watchpoints
+                    .iter()
+                    .enumerate()
+                    .map(|(i, w)| format!("{}: {}", i, w))
+                    .collect();
+                let msg = if list.is_empty() {
+                    "no watchpoints".to_string()
+                } else {
+                    list.join("\n")
+                };
+                (Action::Paused, msg)
+            }
+            Command::Help => (Action::Paused, HELP.to_string()),
+            Command::Quit => (Action::Quit, String::new()),
+        }
+    }
+
+    fn resume(&mut self, machine: &Machine) -> Action {
+        self.paused = false;
+        self.resume_from = Some(machine.cpu.pc);
+        // l'état a pu changer pendant la pause (chargement d'état), on repart de l'état courant
+        self.previous = None;
+        Action::Resume
+    }
+
+    /// Registres, pile, timers et désassemblage autour de `pc`.
+    pub fn state(machine: &Machine) -> String {
+        let cpu = &machine.cpu;
+        let mut out = String::new();
+        let _ = writeln!(
+            out,
+            "PC=#{:03X} I=#{:03X} SP={} DT={:02X} ST={:02X}",
+            cpu.pc, cpu.I, cpu.sp, cpu.game_count, cpu.sound_count
+        );
+        let regs: Vec<String> = cpu
+            .V
+            .iter()
+            .enumerate()
+            .map(|(i, v)| format!("V{:X}={:02X}", i, v))
+            .collect();
+        let _ = writeln!(out, "{}", regs.join(" "));
+        let stack: Vec<String> = cpu.stack[..cpu.sp as usize]
+            .iter()
+            .map(|a| format!("#{:03X}", a))
+            .collect();
+        let _ = writeln!(out, "Stack: [{}]", stack.join(" "));
+        out.push_str(&Self::disassemble_around(machine, 3, 5));
+        out
+    }
+
+    /// Désassemble `before` instructions avant `pc` et `after` après.
+    pub fn disassemble_around(machine: &Machine, before: u16, after: u16) -> String {
+        let cpu = &machine.cpu;
+        let start = cpu.pc.saturating_sub(before * 2);
+        let mut out = String::new();
+
+        for addr in (start..=cpu.pc.saturating_add(after * 2)).step_by(2) {
+            if addr as usize + 1 >= cpu.mem.len() {
+                break;
+            }
+            let opcode = ((cpu.mem[addr as usize] as u16) << 8) | cpu.mem[addr as usize + 1] as u16;
+            let marker = if addr == cpu.pc { "=>" } else { "  " };
+            let _ = writeln!(
+                out,
+                "{} #{:03X}  {:04X}  {}",
+                marker,
+                addr,
+                opcode,
+                Instruction::decode(opcode)
+            );
+        }
+        out
+    }
+
+    fn dump_mem(machine: &Machine, addr: u16, len: u16) -> String {
+        let mem = &machine.cpu.mem;
+        let start = (addr as usize).min(mem.len());
+        let end = (start + len as usize).min(mem.len());
+        let mut out = String::new();
+
+        for (i, chunk) in mem[start..end].chunks(8).enumerate() {
+            let bytes: Vec<String> = chunk.iter().map(|b| format!("{:02X}", b)).collect();
+            let _ = writeln!(out, "#{:03X}: {}", start + i * 8, bytes.join(" "));
+        }
+        out
+    }
+}
+
+fn parse_addr(s: Option<&str>) -> Result<u16, String> {
+    let s = s.ok_or("missing address")?;
+    let digits = s.trim_start_matches('#').trim_start_matches("0x");
+    u16::from_str_radix(digits, 16).map_err(|_| format!("invalid address '{}'", s))
+}
+
+impl FromStr for Command {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut words = s.split_whitespace();
+        let Some(name) = words.next() else {
+            return Ok(Command::Step(1));
+        };
+        let arg = words.next();
+
+        match name.to_ascii_lowercase().as_str() {
+            "c" | "continue" => Ok(Command::Continue),
+            "s" | "step" => {
+                let count = arg.map_or(Ok(1), |n| n.parse().map_err(|_| "invalid count"))?;
+                Ok(Command::Step(count))
+            }
+            "n" | "next" => Ok(Command::Next),
+            "f" | "finish" => Ok(Command::Finish),
+            "b" | "break" => Ok(Command::Break(parse_addr(arg)?)),
+            "d" | "delete" => Ok(Command::Delete(parse_addr(arg)?)),
+            "bl" | "breakpoints" => Ok(Command::Breakpoints),
+            "r" | "regs" => Ok(Command::Regs),
+            "x" => {
+                let addr = parse_addr(arg)?;
+                let len = match words.next() {
+                    Some(n) => parse_addr(Some(n))?,
+                    None => 16,
+                };
+                Ok(Command::Mem { addr, len })
+            }
+            "w" | "watch" => {
+                let spec: Vec<&str> = s.split_whitespace().skip(1).collect();
+                Ok(Command::Watch(spec.join(" ").parse()?))
+            }
+            "wl" | "watchpoints" => Ok(Command::Watchpoints),
+            "wd" => {
+                let index = arg.ok_or("missing watchpoint number")?;
+                index
+                    .parse()
+                    .map(Command::Unwatch)
+                    .map_err(|_| format!("invalid watchpoint number '{}'", index))
+            }
+            "h" | "help" | "?" => Ok(Command::Help),
+            "q" | "quit" => Ok(Command::Quit),
+            _ => Err(format!("unknown command '{}', type 'help'", name)),
+        }
+    }
+}
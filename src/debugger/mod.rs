@@ -0,0 +1,4 @@
+#[allow(clippy::module_inception)]
+pub mod debugger;
+pub mod schema;
+pub mod watch;
@@ -0,0 +1,107 @@
+use std::collections::BTreeSet;
+
+/// État du débogueur pas à pas. Le frontend l'interroge avant chaque instruction
+/// (`should_break`) et lui transmet les commandes saisies (`execute`).
+#[derive(Clone, Debug, Default)]
+pub struct Debugger {
+    pub breakpoints: BTreeSet<u16>,
+    pub paused: bool,
+    pub target: Option<StepTarget>, // arrêt temporaire posé par `next` ou `finish`
+    pub resume_from: Option<u16>,   // ne pas s'arrêter tout de suite sur le point d'arrêt courant
+    pub watchpoints: Vec<Watchpoint>,
+    pub previous: Option<Snapshot>, // registres avant la dernière instruction
+    pub events: Vec<String>,        // déclenchements à afficher par le frontend
+}
+
+/// Point de surveillance : arrête l'exécution, ou se contente de journaliser avec `log_only`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Watchpoint {
+    pub kind: WatchKind,
+    pub log_only: bool,
+    pub active: bool, // dernière valeur d'une condition, on ne déclenche que sur front montant
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WatchKind {
+    /// Accès à la plage `start..=end`.
+    Mem {
+        start: u16,
+        end: u16,
+        access: Access,
+    },
+    /// Changement de valeur d'un registre.
+    Reg(Register),
+    /// Condition sur un registre, par exemple `V3 == 10`.
+    Cond {
+        reg: Register,
+        op: CmpOp,
+        value: u16,
+    },
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Access {
+    Read,
+    Write,
+    ReadWrite,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Register {
+    V(u8),
+    I,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CmpOp {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+/// Registres relevés avant une instruction, pour détecter leurs changements.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Snapshot {
+    pub pc: u16,
+    pub v: [u8; 16],
+    pub i: u16,
+}
+
+/// Arrêt temporaire pour sauter par-dessus un appel ou sortir d'un sous-programme.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StepTarget {
+    /// Retour juste après le 2NNN, au même niveau de pile.
+    Over { pc: u16, sp: u8 },
+    /// Premier arrêt avec une pile moins profonde, donc après le 00EE correspondant.
+    Out { sp: u8 },
+}
+
+/// Commande saisie à l'invite du débogueur.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Command {
+    Continue,
+    Step(u32),
+    Next,
+    Finish,
+    Break(u16),
+    Delete(u16),
+    Breakpoints,
+    Regs,
+    Mem { addr: u16, len: u16 },
+    Watch(Watchpoint),
+    Unwatch(usize),
+    Watchpoints,
+    Help,
+    Quit,
+}
+
+/// Ce que le frontend doit faire après une commande.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Action {
+    Paused,
+    Resume,
+    Quit,
+}
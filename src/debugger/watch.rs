@@ -0,0 +1,287 @@
+use std::{fmt, str::FromStr};
+
+use crate::cpu::schema::{Instruction, CPU};
+use crate::machine::schema::Machine;
+
+use super::schema::{Access, CmpOp, Debugger, Register, Snapshot, WatchKind, Watchpoint};
+
+impl Debugger {
+    /// Ajoute un point de surveillance. Une condition déjà vraie ne se déclenchera
+    /// qu'après être redevenue fausse.
+    pub fn add_watch(&mut self, mut watch: Watchpoint, machine: &Machine) -> usize {
+        if let WatchKind::Cond { reg, op, value } = watch.kind {
+            watch.active = op.eval(reg.get(&machine.cpu), value);
+        }
+        self.watchpoints.push(watch);
+        self.watchpoints.len() - 1
+    }
+
+    /// Évalue les points de surveillance avant l'instruction à `pc`.
+    /// Les accès mémoire sont vérifiés avant exécution, les registres après coup.
+    pub(super) fn check_watchpoints(&mut self, machine: &Machine, resuming: bool) -> bool {
+        let cpu = &machine.cpu;
+        let previous = self.previous.replace(Snapshot::of(cpu));
+        if self.watchpoints.is_empty() {
+            return false;
+        }
+        // on vient de s'arrêter sur cette instruction, ne pas redéclencher
+        let access = if resuming { None } else { mem_access(machine) };
+        let mut hit = false;
+
+        for watch in &mut self.watchpoints {
+            let message = match watch.kind {
+                WatchKind::Mem {
+                    start,
+                    end,
+                    access: wanted,
+                } => access
+                    .filter(|&(kind, from, to)| wanted.covers(kind) && from <= end && start <= to)
+                    .map(|(kind, from, to)| {
+                        format!(
+                            "{} #{:03X}-#{:03X} by {} at #{:03X}",
+                            kind,
+                            from,
+                            to,
+                            Instruction::decode(cpu.get_opcode()),
+                            cpu.pc
+                        )
+                    }),
+                WatchKind::Reg(reg) => previous.and_then(|prev| {
+                    let (old, new) = (prev.get(reg), reg.get(cpu));
+                    (old != new).then(|| {
+                        format!(
+                            "{} changed #{:X} -> #{:X} at #{:03X}",
+                            reg, old, new, prev.pc
+                        )
+                    })
+                }),
+                WatchKind::Cond { reg, op, value } => {
+                    let now = op.eval(reg.get(cpu), value);
+                    let fired = now && !watch.active;
+                    watch.active = now;
+                    fired.then(|| {
+                        let at = previous.map_or(cpu.pc, |prev| prev.pc);
+                        format!("{} {} #{:X} became true at #{:03X}", reg, op, value, at)
+                    })
+                }
+            };
+
+            if let Some(message) = message {
+                if watch.log_only {
+                    self.events.push(format!("watch: {}", message));
+                } else {
+                    self.events.push(format!("Watchpoint: {}", message));
+                    hit = true;
+                }
+            }
+        }
+        hit
+    }
+}
+
+/// Plage mémoire lue ou écrite par l'instruction à `pc`, calculée avant son exécution.
+fn mem_access(machine: &Machine) -> Option<(Access, u16, u16)> {
+    let cpu = &machine.cpu;
+    if cpu.check_fetch().is_err() {
+        return None;
+    }
+    let range = |len: u16| (cpu.I, cpu.I.wrapping_add(len.max(1) - 1));
+
+    let (access, len) = match Instruction::decode(cpu.get_opcode()) {
+        Instruction::Drw { n, .. } => {
+            let bytes = if n == 0 { 32 } else { n as u16 };
+            (
+                Access::Read,
+                bytes * machine.screen.planes.count_ones() as u16,
+            )
+        }
+        Instruction::LdB { .. } => (Access::Write, 3),
+        Instruction::LdIVx { x } => (Access::Write, x as u16 + 1),
+        Instruction::LdVxI { x } => (Access::Read, x as u16 + 1),
+        Instruction::SaveRange { x, y } => (Access::Write, x.abs_diff(y) as u16 + 1),
+        Instruction::LoadRange { x, y } => (Access::Read, x.abs_diff(y) as u16 + 1),
+        Instruction::Audio => (Access::Read, 16),
+        _ => return None,
+    };
+    let (start, end) = range(len);
+    Some((access, start, end))
+}
+
+impl Snapshot {
+    pub fn of(cpu: &CPU) -> Self {
+        Snapshot {
+            pc: cpu.pc,
+            v: cpu.V,
+            i: cpu.I,
+        }
+    }
+
+    fn get(&self, reg: Register) -> u16 {
+        match reg {
+            Register::V(x) => self.v[x as usize] as u16,
+            Register::I => self.i,
+        }
+    }
+}
+
+impl Register {
+    pub fn get(self, cpu: &CPU) -> u16 {
+        match self {
+            Register::V(x) => cpu.V[x as usize] as u16,
+            Register::I => cpu.I,
+        }
+    }
+}
+
+impl Access {
+    fn covers(self, other: Access) -> bool {
+        self == Access::ReadWrite || self == other
+    }
+}
+
+impl CmpOp {
+    pub fn eval(self, a: u16, b: u16) -> bool {
+        match self {
+            CmpOp::Eq => a == b,
+            CmpOp::Ne => a != b,
+            CmpOp::Lt => a < b,
+            CmpOp::Le => a <= b,
+            CmpOp::Gt => a > b,
+            CmpOp::Ge => a >= b,
+        }
+    }
+}
+
+fn parse_hex(s: &str) -> Result<u16, String> {
+    let digits = s.trim_start_matches('#').trim_start_matches("0x");
+    u16::from_str_radix(digits, 16).map_err(|_| format!("invalid hex value '{}'", s))
+}
+
+impl FromStr for Register {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let upper = s.to_ascii_uppercase();
+        match upper.strip_prefix('V') {
+            _ if upper == "I" => Ok(Register::I),
+            Some(x) if x.len() == 1 => u8::from_str_radix(x, 16)
+                .map(Register::V)
+                .map_err(|_| format!("invalid register '{}'", s)),
+            _ => Err(format!("invalid register '{}'", s)),
+        }
+    }
+}
+
+impl FromStr for CmpOp {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "==" => Ok(CmpOp::Eq),
+            "!=" => Ok(CmpOp::Ne),
+            "<" => Ok(CmpOp::Lt),
+            "<=" => Ok(CmpOp::Le),
+            ">" => Ok(CmpOp::Gt),
+            ">=" => Ok(CmpOp::Ge),
+            _ => Err(format!("invalid comparison '{}'", s)),
+        }
+    }
+}
+
+/// `ADDR[-END] [r|w|rw] [log]`, `REG [log]` ou `REG OP VALUE [log]`, valeurs en hexadécimal.
+impl FromStr for Watchpoint {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut words: Vec<&str> = s.split_whitespace().collect();
+        let log_only = words.last() == Some(&"log");
+        if log_only {
+            words.pop();
+        }
+
+        let kind = match words.as_slice() {
+            [reg] if reg.parse::<Register>().is_ok() => WatchKind::Reg(reg.parse()?),
+            [reg, op, value] => WatchKind::Cond {
+                reg: reg.parse()?,
+                op: op.parse()?,
+                value: parse_hex(value)?,
+            },
+            [range, rest @ ..] if rest.len() <= 1 => {
+                let (start, end) = match range.split_once('-') {
+                    Some((a, b)) => (parse_hex(a)?, parse_hex(b)?),
+                    None => (parse_hex(range)?, parse_hex(range)?),
+                };
+                if end < start {
+                    return Err(format!("empty range '{}'", range));
+                }
+                let access = match rest.first().copied() {
+                    None | Some("w") => Access::Write,
+                    Some("r") => Access::Read,
+                    Some("rw") => Access::ReadWrite,
+                    Some(other) => return Err(format!("invalid access '{}'", other)),
+                };
+                WatchKind::Mem { start, end, access }
+            }
+            _ => return Err(format!("invalid watchpoint '{}'", s)),
+        };
+
+        Ok(Watchpoint {
+            kind,
+            log_only,
+            active: false,
+        })
+    }
+}
+
+impl fmt::Display for Register {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Register::V(x) => write!(f, "V{:X}", x),
+            Register::I => write!(f, "I"),
+        }
+    }
+}
+
+impl fmt::Display for CmpOp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let op = match self {
+            CmpOp::Eq => "==",
+            CmpOp::Ne => "!=",
+            CmpOp::Lt => "<",
+            CmpOp::Le => "<=",
+            CmpOp::Gt => ">",
+            CmpOp::Ge => ">=",
+        };
+        write!(f, "{}", op)
+    }
+}
+
+impl fmt::Display for Access {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let access = match self {
+            Access::Read => "read",
+            Access::Write => "write",
+            Access::ReadWrite => "access",
+        };
+        write!(f, "{}", access)
+    }
+}
+
+impl fmt::Display for Watchpoint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.kind {
+            WatchKind::Mem { start, end, access } if start == end => {
+                write!(f, "{} #{:03X}", access, start)?
+            }
+            WatchKind::Mem { start, end, access } => {
+                write!(f, "{} #{:03X}-#{:03X}", access, start, end)?
+            }
+            WatchKind::Reg(reg) => write!(f, "change {}", reg)?,
+            WatchKind::Cond { reg, op, value } => write!(f, "{} {} #{:X}", reg, op, value)?,
+        }
+        if self.log_only {
+            write!(f, " (log)")?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,231 @@
+use std::fmt::Write;
+
+use crate::cpu::schema::{Instruction, Profile};
+
+use super::schema::{Disassembly, LabelKind, DB_PER_LINE};
+
+impl Disassembly {
+    /// Parcourt le flot d'exécution depuis `origin` pour séparer le code des données.
+    /// Les instructions inconnues du profil arrêtent le parcours et restent des données.
+    pub fn analyze(rom: &[u8], origin: u16, profile: Profile) -> Self {
+        let mut dis = Disassembly {
+            origin,
+            rom: rom.to_vec(),
+            profile,
+            code: Default::default(),
+            long_words: Default::default(),
+            labels: Default::default(),
+        };
+        let mut pending = vec![origin];
+
+        while let Some(addr) = pending.pop() {
+            let Some(opcode) = dis.word(addr) else {
+                continue;
+            };
+            // déjà vu, ou chevauche une instruction connue
+            if dis.code.contains(&addr)
+                || dis.code.contains(&addr.wrapping_sub(1))
+                || dis.code.contains(&addr.wrapping_add(1))
+            {
+                continue;
+            }
+            let instruction = Instruction::decode(opcode);
+            if !profile.supports(instruction) {
+                continue;
+            }
+            dis.code.insert(addr);
+            let next = addr.wrapping_add(2);
+
+            match instruction {
+                Instruction::Jp { nnn } => {
+                    dis.add_label(nnn, LabelKind::Jump);
+                    pending.push(nnn);
+                }
+                Instruction::Call { nnn } => {
+                    dis.add_label(nnn, LabelKind::Sub);
+                    pending.extend([next, nnn]);
+                }
+                // la cible dépend de V0 : on ne suit que le début de la table
+                Instruction::JpV0 { nnn, .. } => {
+                    dis.add_label(nnn, LabelKind::Jump);
+                    pending.push(nnn);
+                }
+                Instruction::Ret | Instruction::Exit => {}
+                Instruction::SeImm { .. }
+                | Instruction::SneImm { .. }
+                | Instruction::SeReg { .. }
+                | Instruction::SneReg { .. }
+                | Instruction::Skp { .. }
+                | Instruction::Sknp { .. } => {
+                    // sur XO-CHIP, sauter un F000 NNNN saute 4 octets
+                    let skipped = match dis.word(next) {
+                        Some(0xF000) if profile.supports(Instruction::LdILong) => 4,
+                        _ => 2,
+                    };
+                    pending.extend([next.wrapping_add(skipped), next]);
+                }
+                Instruction::LdI { nnn } => {
+                    dis.add_label(nnn, LabelKind::Data);
+                    pending.push(next);
+                }
+                Instruction::LdILong => {
+                    if let Some(target) = dis.word(next) {
+                        dis.long_words.insert(next);
+                        dis.add_label(target, LabelKind::Data);
+                    }
+                    pending.push(next.wrapping_add(2));
+                }
+                _ => pending.push(next),
+            }
+        }
+        dis
+    }
+
+    fn word(&self, addr: u16) -> Option<u16> {
+        let offset = addr.checked_sub(self.origin)? as usize;
+        let bytes = self.rom.get(offset..offset + 2)?;
+        Some(((bytes[0] as u16) << 8) | bytes[1] as u16)
+    }
+
+    /// Fin de la ROM, exclue ; vaut 0x10000 pour une image qui remplit l'espace adressable.
+    fn end(&self) -> usize {
+        self.origin as usize + self.rom.len()
+    }
+
+    fn add_label(&mut self, addr: u16, kind: LabelKind) {
+        let entry = self.labels.entry(addr).or_insert(kind);
+        *entry = (*entry).max(kind);
+    }
+
+    /// Une étiquette n'est utilisable que si elle tombe au début d'une ligne de la ROM.
+    fn label(&self, addr: u16) -> Option<String> {
+        let kind = self.labels.get(&addr)?;
+        let inside = addr < self.origin
+            || addr as usize >= self.end()
+            || self.code.contains(&addr.wrapping_sub(1))
+            || self.long_words.contains(&addr.wrapping_sub(1));
+        if inside {
+            return None;
+        }
+        let prefix = match kind {
+            LabelKind::Sub => "SUB",
+            LabelKind::Jump => "LBL",
+            LabelKind::Data => "DATA",
+        };
+        Some(format!("{}_{:03X}", prefix, addr))
+    }
+
+    /// Source CHIPPER réassemblable octet pour octet.
+    pub fn to_source(&self) -> String {
+        let mut out = String::new();
+        let _ = writeln!(out, "; Disassembled for the {:?} profile", self.profile);
+        if self.origin != 0x200 {
+            let _ = writeln!(out, "; Load address #{:03X}", self.origin);
+        }
+        let _ = writeln!(out, "\nOPTION BINARY\nALIGN OFF\n");
+
+        let mut next = self.origin as usize;
+        let mut sprite = false; // données désignées par un LD I, affichées bit à bit
+        while next < self.end() {
+            let addr = next as u16;
+            if let Some(label) = self.label(addr) {
+                let _ = writeln!(out, "\n{}:", label);
+                sprite = self.labels.get(&addr) == Some(&LabelKind::Data);
+            }
+
+            if self.code.contains(&addr) {
+                let instruction = Instruction::decode(self.word(addr).unwrap_or(0));
+                let _ = writeln!(out, "    {}", self.format_instruction(instruction, addr));
+                next += 2;
+                sprite = false;
+            } else if self.long_words.contains(&addr) {
+                let target = self.word(addr).unwrap_or(0);
+                let operand = self
+                    .label(target)
+                    .unwrap_or_else(|| format!("#{:04X}", target));
+                let _ = writeln!(out, "    DW {}", operand);
+                next += 2;
+            } else {
+                next = self.format_data(&mut out, next, sprite);
+            }
+        }
+        out
+    }
+
+    /// Écrit les octets de données jusqu'au prochain code ou étiquette, et retourne l'adresse suivante.
+    fn format_data(&self, out: &mut String, start: usize, sprite: bool) -> usize {
+        let mut end = start + 1;
+        while end < self.end()
+            && !self.code.contains(&(end as u16))
+            && !self.long_words.contains(&(end as u16))
+            && self.label(end as u16).is_none()
+        {
+            end += 1;
+        }
+        let origin = self.origin as usize;
+        let bytes = &self.rom[start - origin..end - origin];
+
+        if sprite {
+            for byte in bytes {
+                let bits: String = (0..8)
+                    .map(|bit| if byte & (0x80 >> bit) != 0 { '1' } else { '.' })
+                    .collect();
+                let _ = writeln!(out, "    DB ${}    ; #{:02X}", bits, byte);
+            }
+        } else {
+            for chunk in bytes.chunks(DB_PER_LINE) {
+                let list: Vec<String> = chunk.iter().map(|b| format!("#{:02X}", b)).collect();
+                let _ = writeln!(out, "    DB {}", list.join(", "));
+            }
+        }
+        end
+    }
+
+    /// Mnémonique avec l'adresse remplacée par son étiquette et un commentaire
+    /// lorsque les quirks du profil changent le sens de l'instruction.
+    fn format_instruction(&self, instruction: Instruction, addr: u16) -> String {
+        let mut text = instruction.to_string();
+        let target = match instruction {
+            Instruction::Jp { nnn }
+            | Instruction::Call { nnn }
+            | Instruction::LdI { nnn }
+            | Instruction::JpV0 { nnn, .. } => Some(nnn),
+            _ => None,
+        };
+        if let Some(label) = target.and_then(|nnn| self.label(nnn)) {
+            if let Some(pos) = text.rfind('#') {
+                text.replace_range(pos.., &label);
+            }
+        }
+
+        let quirks = self.profile.quirks();
+        let note = match instruction {
+            Instruction::Jp { nnn } if nnn == addr => Some("halt".to_string()),
+            Instruction::Shr { x, y } | Instruction::Shl { x, y } if quirks.shift_uses_vy => {
+                Some(format!("V{:X} = V{:X} shifted, VF = bit out", x, y))
+            }
+            Instruction::Shr { x, y } | Instruction::Shl { x, y } if y != 0 && y != x => {
+                Some(format!("V{:X} is ignored", y))
+            }
+            Instruction::JpV0 { x, nnn } if quirks.jump_uses_vx => {
+                Some(format!("jumps to V{:X} + #{:03X}", x, nnn))
+            }
+            Instruction::LdIVx { x } | Instruction::LdVxI { x }
+                if quirks.load_store_increments_i =>
+            {
+                Some(format!("I += {}", x as u16 + 1))
+            }
+            Instruction::Or { .. } | Instruction::And { .. } | Instruction::Xor { .. }
+                if quirks.logic_resets_vf =>
+            {
+                Some("VF = 0".to_string())
+            }
+            _ => None,
+        };
+
+        match note {
+            Some(note) => format!("{:<20}; {}", text, note),
+            None => text,
+        }
+    }
+}
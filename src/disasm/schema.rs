@@ -0,0 +1,25 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::cpu::schema::Profile;
+
+/// Nombre d'octets par ligne `DB` pour les données qui ne sont pas des sprites.
+pub const DB_PER_LINE: usize = 8;
+
+/// Type d'étiquette générée, par ordre de priorité croissante.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum LabelKind {
+    Data,
+    Jump,
+    Sub,
+}
+
+/// Résultat de l'analyse d'une ROM : ce qui est du code, ce qui est des données.
+#[derive(Clone, Debug)]
+pub struct Disassembly {
+    pub origin: u16,
+    pub rom: Vec<u8>,
+    pub profile: Profile,
+    pub code: BTreeSet<u16>, // adresses de début d'instruction atteintes
+    pub long_words: BTreeSet<u16>, // mot de 16 bits qui suit un F000
+    pub labels: BTreeMap<u16, LabelKind>,
+}
@@ -0,0 +1,105 @@
+use std::{
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::Path,
+};
+
+use super::schema::{FrameBuffer, Palette, BLACK};
+
+impl FrameBuffer {
+    /// Copie la zone active de l'écran sous forme de lignes de booléens (`true` = pixel allumé).
+    pub fn as_bits(&self) -> Vec<Vec<bool>> {
+        (0..self.height() as usize)
+            .map(|y| {
+                (0..self.width() as usize)
+                    .map(|x| self.pixel[x][y] != BLACK)
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Rendu texte de l'écran : `#` pour un pixel allumé, `.` sinon.
+    pub fn to_text(&self) -> String {
+        let mut out = String::with_capacity(((self.width() + 1) * self.height()) as usize);
+        for row in self.as_bits() {
+            for bit in row {
+                out.push(if bit { '#' } else { '.' });
+            }
+            out.push('\n');
+        }
+        out
+    }
+
+    /// Écrit l'écran au format PBM ASCII (P1), 1 = pixel allumé ; chaque pixel logique
+    /// devient un carré de `scale` pixels.
+    pub fn write_pbm<Wr: Write>(&self, out: &mut Wr, scale: u32) -> io::Result<()> {
+        let scale = scale.max(1) as usize;
+        writeln!(out, "P1")?;
+        writeln!(
+            out,
+            "{} {}",
+            self.width() as usize * scale,
+            self.height() as usize * scale
+        )?;
+        for row in self.as_bits() {
+            let line: Vec<&str> = row
+                .iter()
+                .flat_map(|&b| std::iter::repeat_n(if b { "1" } else { "0" }, scale))
+                .collect();
+            let line = line.join(" ");
+            for _ in 0..scale {
+                writeln!(out, "{}", line)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Écrit l'écran en PNG RGB avec `palette`, agrandi `scale` fois.
+    pub fn write_png<Wr: Write>(
+        &self,
+        out: &mut Wr,
+        palette: &Palette,
+        scale: u32,
+    ) -> io::Result<()> {
+        let scale = scale.max(1);
+        let too_large = || {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("scale {} makes the image too large", scale),
+            )
+        };
+        let width = self.width().checked_mul(scale).ok_or_else(too_large)?;
+        let height = self.height().checked_mul(scale).ok_or_else(too_large)?;
+        let size = (width as usize)
+            .checked_mul(height as usize)
+            .and_then(|pixels| pixels.checked_mul(3))
+            .ok_or_else(too_large)?;
+        let mut encoder = png::Encoder::new(out, width, height);
+        encoder.set_color(png::ColorType::Rgb);
+        encoder.set_depth(png::BitDepth::Eight);
+
+        let mut data = Vec::with_capacity(size);
+        for y in 0..height {
+            for x in 0..width {
+                let value = self.pixel[(x / scale) as usize][(y / scale) as usize];
+                data.extend_from_slice(&palette[(value & 0x3) as usize]);
+            }
+        }
+
+        let mut writer = encoder.write_header()?;
+        writer.write_image_data(&data)?;
+        Ok(())
+    }
+
+    /// Enregistre une capture : PBM si l'extension est `.pbm`, PNG sinon.
+    pub fn save_image(&self, path: &Path, palette: &Palette, scale: u32) -> io::Result<()> {
+        let mut out = BufWriter::new(File::create(path)?);
+        let ext = path.extension().and_then(|e| e.to_str());
+        if ext.is_some_and(|e| e.eq_ignore_ascii_case("pbm")) {
+            self.write_pbm(&mut out, scale)?;
+        } else {
+            self.write_png(&mut out, palette, scale)?;
+        }
+        out.flush()
+    }
+}
@@ -1,2 +1,5 @@
+pub mod dump;
+pub mod palette;
 pub mod pixel;
+pub mod scaling;
 pub mod schema;
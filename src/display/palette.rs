@@ -0,0 +1,52 @@
+use super::schema::{Palette, PALETTE_PRESETS};
+
+/// Lit une palette : un préréglage (`amber`, `octo`...) ou des couleurs `#RRGGBB`
+/// séparées par des virgules. Deux couleurs donnent le fond et les pixels allumés,
+/// les plans XO-CHIP prennent alors des teintes intermédiaires ; quatre couleurs
+/// donnent chaque valeur de pixel, de 0 à 3.
+pub fn parse_palette(spec: &str) -> Result<Palette, String> {
+    if let Some((_, palette)) = PALETTE_PRESETS
+        .iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case(spec.trim()))
+    {
+        return Ok(*palette);
+    }
+    let parts: Vec<&str> = spec.split(',').collect();
+    if parts.len() != 2 && parts.len() != 4 {
+        let names: Vec<&str> = PALETTE_PRESETS.iter().map(|(name, _)| *name).collect();
+        return Err(format!(
+            "palette '{}' should be one of {} or 2 or 4 colors like #000000,#FFB000",
+            spec,
+            names.join(", ")
+        ));
+    }
+    let colors = parts
+        .into_iter()
+        .map(parse_color)
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(match colors[..] {
+        [background, foreground] => [
+            background,
+            foreground,
+            mix(background, foreground, 1, 3),
+            mix(background, foreground, 2, 3),
+        ],
+        _ => [colors[0], colors[1], colors[2], colors[3]],
+    })
+}
+
+/// `#RRGGBB`, le `#` est facultatif.
+pub fn parse_color(text: &str) -> Result<[u8; 3], String> {
+    let hex = text.trim().trim_start_matches('#');
+    let value = Some(hex)
+        .filter(|h| h.len() == 6 && h.bytes().all(|b| b.is_ascii_hexdigit()))
+        .and_then(|h| u32::from_str_radix(h, 16).ok())
+        .ok_or_else(|| format!("'{}' is not a color, expected #RRGGBB", text.trim()))?;
+    Ok([(value >> 16) as u8, (value >> 8) as u8, value as u8])
+}
+
+/// Couleur aux `num / den` du chemin de `from` vers `to`.
+fn mix(from: [u8; 3], to: [u8; 3], num: u16, den: u16) -> [u8; 3] {
+    let channel = |i: usize| ((from[i] as u16 * (den - num) + to[i] as u16 * num) / den) as u8;
+    [channel(0), channel(1), channel(2)]
+}
@@ -1,125 +1,144 @@
-use sdl2::{
-    pixels::{Color, PixelFormatEnum},
-    rect::Rect,
-    render::{Canvas, TextureCreator},
-    surface::Surface,
-    video::{Window, WindowContext},
-};
-
-use crate::cpu::schema::{Keyboard, CPU, MEM_SIZE};
-
-use super::schema::{ContextPixels, Pixel, BLACK, DIMPIXEL, H, W, WHITE};
-
-impl Pixel {
-    pub fn new(pos: Rect) -> Self {
-        Pixel {
-            color: BLACK,
-            position: pos,
+use crate::cpu::schema::CPU;
+
+use super::schema::{FrameBuffer, BLACK, H, HIRES_H, HIRES_W, NBR_PLANES, W};
+
+impl FrameBuffer {
+    pub fn new() -> Self {
+        FrameBuffer {
+            pixel: [[BLACK; HIRES_H as usize]; HIRES_W as usize],
+            hires: false,
+            planes: 1,
         }
     }
-}
 
-impl<'a> ContextPixels<'a> {
-    pub fn init(
-        screen: Canvas<Window>,
-        texture_creator: &'a TextureCreator<WindowContext>,
-    ) -> Self {
-        // créer les surfaces et les convertir en textures
-        let mut surf_black =
-            Surface::new(DIMPIXEL as u32, DIMPIXEL as u32, PixelFormatEnum::RGB24).unwrap();
-        surf_black.fill_rect(None, Color::RGB(0, 0, 0)).unwrap();
-
-        let mut surf_white =
-            Surface::new(DIMPIXEL as u32, DIMPIXEL as u32, PixelFormatEnum::RGB24).unwrap();
-        surf_white
-            .fill_rect(None, Color::RGB(255, 255, 255))
-            .unwrap();
-
-        let tex_black = texture_creator
-            .create_texture_from_surface(&surf_black)
-            .expect("Erreur texture noire");
-
-        let tex_white = texture_creator
-            .create_texture_from_surface(&surf_white)
-            .expect("Erreur texture blanche");
-
-        // Initialisation pixels
-        let mut pixel = [[Pixel::new(Rect::new(0, 0, DIMPIXEL as u32, DIMPIXEL as u32));
-            H as usize]; W as usize];
-
-        for x in 0..W {
-            for y in 0..H {
-                pixel[x as usize][y as usize]
-                    .position
-                    .set_x((x as i32) * (DIMPIXEL as i32));
-                pixel[x as usize][y as usize]
-                    .position
-                    .set_y((y as i32) * (DIMPIXEL as i32));
-
-                pixel[x as usize][y as usize].color = BLACK;
-            }
+    pub fn width(&self) -> u32 {
+        if self.hires {
+            HIRES_W
+        } else {
+            W
         }
+    }
 
-        Self {
-            screen,
-            textures: [tex_black, tex_white],
-            pixel,
-            keyboard: Keyboard::new(),
+    pub fn height(&self) -> u32 {
+        if self.hires {
+            HIRES_H
+        } else {
+            H
         }
     }
 
-    pub fn draw_pixel(&mut self, pixel: &Pixel) {
-        let texture = &self.textures[pixel.color as usize];
-
-        self.screen
-            .copy(&texture, None, Some(pixel.position))
-            .expect("error during pixel render");
+    /// 00FE/00FF bascule entre 64x32 et 128x64, l'écran est effacé au passage.
+    pub fn set_hires(&mut self, hires: bool) {
+        self.hires = hires;
+        self.clear_screen();
     }
 
+    /// 00E0 efface les plans sélectionnés.
     pub fn clear_screen(&mut self) {
-        for x in 0..W {
-            for y in 0..H {
-                self.pixel[x as usize][y as usize].color = BLACK;
+        let mask = self.planes;
+        for column in self.pixel.iter_mut() {
+            for p in column.iter_mut() {
+                *p &= !mask;
             }
         }
-
-        self.screen.set_draw_color(Color::BLACK);
-        self.screen.clear();
     }
 
-    pub fn update_screen(&mut self) {
-        for x in 0..W as usize {
-            for y in 0..H as usize {
-                self.draw_pixel(&self.pixel[x][y].clone());
+    /// Décale le contenu des plans sélectionnés de (`dx`, `dy`), ce qui sort de l'écran est perdu.
+    fn scroll(&mut self, dx: i32, dy: i32) {
+        let (w, h) = (self.width() as i32, self.height() as i32);
+        let mask = self.planes;
+        let old = self.pixel;
+
+        for x in 0..w {
+            for y in 0..h {
+                let (src_x, src_y) = (x - dx, y - dy);
+                let moved = if (0..w).contains(&src_x) && (0..h).contains(&src_y) {
+                    old[src_x as usize][src_y as usize] & mask
+                } else {
+                    BLACK
+                };
+                let p = &mut self.pixel[x as usize][y as usize];
+                *p = (*p & !mask) | moved;
             }
         }
-        self.screen.present();
+    }
+
+    /// 00CN fait descendre l'écran de `n` lignes.
+    pub fn scroll_down(&mut self, n: u8) {
+        self.scroll(0, n as i32);
+    }
+
+    /// 00DN fait monter l'écran de `n` lignes (XO-CHIP).
+    pub fn scroll_up(&mut self, n: u8) {
+        self.scroll(0, -(n as i32));
+    }
+
+    /// 00FB décale l'écran de `n` colonnes vers la droite.
+    pub fn scroll_right(&mut self, n: u8) {
+        self.scroll(n as i32, 0);
+    }
+
+    /// 00FC décale l'écran de `n` colonnes vers la gauche.
+    pub fn scroll_left(&mut self, n: u8) {
+        self.scroll(-(n as i32), 0);
+    }
+
+    /// DXYN dessine un sprite de 8 x N, DXY0 un sprite SUPER-CHIP de 16 x 16.
+    /// Avec plusieurs plans sélectionnés, les données de chaque plan se suivent en mémoire.
+    /// Nombre d'octets lus à partir de `I` pour un sprite DXYN sur les plans actifs.
+    pub fn sprite_len(&self, n: u8) -> usize {
+        let bytes = if n == 0 { 32 } else { n as usize };
+        bytes * self.planes.count_ones() as usize
     }
 
     pub fn draw_screen(&mut self, n: u8, x: u8, y: u8, cpu: &mut CPU) {
-        //let k: u8 = 0;
+        let (w, h) = (self.width() as usize, self.height() as usize);
+        let (rows, bytes_per_row) = if n == 0 { (16, 2) } else { (n as usize, 1) };
+        let mut sprite_addr = cpu.I as usize;
 
         cpu.V[0xF] = 0;
 
-        for byte_index in 0..n {
-            let sprite_addr = cpu.I.wrapping_add(byte_index as u16);
-            if sprite_addr as usize > MEM_SIZE {
-                break;
+        for plane in (0..NBR_PLANES).map(|p| 1 << p) {
+            if self.planes & plane == 0 {
+                continue;
             }
-            let sprite_byte = cpu.mem[sprite_addr as usize];
-            let y_pos = ((cpu.V[y as usize] as usize + byte_index as usize) % H as usize) as usize;
 
-            for bit_index in 0..8 {
-                let x_pos = ((cpu.V[x as usize] as usize + bit_index) % W as usize) as usize;
+            for row in 0..rows {
+                let y_pos = cpu.V[y as usize] as usize % h + row;
+                let clipped = y_pos >= h && cpu.quirks.clip_sprites;
+                let y_pos = y_pos % h;
 
-                let bit = (sprite_byte >> (7 - bit_index)) & 1;
-                if bit == 1 {
-                    if self.pixel[x_pos][y_pos].color == WHITE {
-                        cpu.V[0xF] = 1; // colision
+                for byte_index in 0..bytes_per_row {
+                    let addr = sprite_addr + row * bytes_per_row + byte_index;
+                    if clipped {
+                        continue;
+                    }
+                    let sprite_byte = cpu.mem[addr];
+
+                    for bit_index in 0..8 {
+                        let x_pos = cpu.V[x as usize] as usize % w + byte_index * 8 + bit_index;
+                        if x_pos >= w && cpu.quirks.clip_sprites {
+                            break;
+                        }
+                        let x_pos = x_pos % w;
+
+                        let bit = (sprite_byte >> (7 - bit_index)) & 1;
+                        if bit == 1 {
+                            if self.pixel[x_pos][y_pos] & plane != 0 {
+                                cpu.V[0xF] = 1; // colision
+                            }
+                            self.pixel[x_pos][y_pos] ^= plane;
+                        }
                     }
-                    self.pixel[x_pos][y_pos].color ^= 1;
                 }
             }
+            sprite_addr += rows * bytes_per_row;
         }
     }
 }
+
+impl Default for FrameBuffer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,54 @@
+use std::str::FromStr;
+
+use super::schema::{Scaling, Viewport};
+
+impl Scaling {
+    /// Place `cols` x `rows` pixels logiques au centre d'une sortie `width` x `height`.
+    /// Une fenêtre plus petite que l'écran logique passe en mise à l'échelle proportionnelle.
+    pub fn viewport(self, (width, height): (u32, u32), (cols, rows): (u32, u32)) -> Viewport {
+        let cell = (width / cols).min(height / rows);
+        let (w, h) = match self {
+            Scaling::Integer if cell > 0 => (cols * cell, rows * cell),
+            // comparaison des rapports sans division pour ne pas perdre de pixel
+            _ if width as u64 * rows as u64 > height as u64 * cols as u64 => {
+                (height * cols / rows, height)
+            }
+            _ => (width, width * rows / cols),
+        };
+        Viewport {
+            x: (width - w) / 2,
+            y: (height - h) / 2,
+            width: w,
+            height: h,
+            cols,
+            rows,
+        }
+    }
+}
+
+impl Viewport {
+    /// Rectangle (x, y, largeur, hauteur) du pixel logique (`col`, `row`). Les bords sont
+    /// arrondis de façon que les pixels voisins se touchent sans se chevaucher.
+    pub fn cell(&self, col: u32, row: u32) -> (i32, i32, u32, u32) {
+        let left = self.x + col * self.width / self.cols;
+        let right = self.x + (col + 1) * self.width / self.cols;
+        let top = self.y + row * self.height / self.rows;
+        let bottom = self.y + (row + 1) * self.height / self.rows;
+        (left as i32, top as i32, right - left, bottom - top)
+    }
+}
+
+impl FromStr for Scaling {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "integer" => Ok(Scaling::Integer),
+            "aspect" => Ok(Scaling::Aspect),
+            _ => Err(format!(
+                "unknown scaling '{}', expected integer or aspect",
+                s
+            )),
+        }
+    }
+}
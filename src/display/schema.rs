@@ -1,26 +1,92 @@
-use sdl2::rect::Rect;
-use sdl2::render::{Canvas, Texture};
-use sdl2::video::Window;
-
-use crate::cpu::schema::Keyboard;
-
 pub const BLACK: u8 = 0;
 pub const WHITE: u8 = 1;
 pub const W: u32 = 64; // nombre de pixels suivant la largeur
 pub const H: u32 = 32; // nombre de pixels suivant la longueur
-pub const DIMPIXEL: u32 = 8; // pixel carre de cote 8
-pub const WIDHT: u32 = W * DIMPIXEL; // largeur de l'écran
-pub const HEIGHT: u32 = H * DIMPIXEL; // longueur de l'écran
-
-#[derive(Clone, Copy)]
-pub struct Pixel {
-    pub position: Rect,
-    pub color: u8,
+pub const HIRES_W: u32 = 128; // largeur en mode haute résolution (SUPER-CHIP)
+pub const HIRES_H: u32 = 64; // hauteur en mode haute résolution (SUPER-CHIP)
+pub const NBR_PLANES: u8 = 2; // plans de bits XO-CHIP
+pub const DEFAULT_WINDOW_SCALE: u32 = 8; // côté d'un pixel 64x32 dans la fenêtre au démarrage
+
+/// Couleur RGB de chaque valeur de pixel : bit 0 = plan 1, bit 1 = plan 2.
+pub type Palette = [[u8; 3]; 4];
+
+pub const DEFAULT_PALETTE: Palette = [
+    [0x00, 0x00, 0x00], // aucun plan
+    [0xFF, 0xFF, 0xFF], // plan 1
+    [0xAA, 0xAA, 0xAA], // plan 2
+    [0x55, 0x55, 0x55], // plans 1 et 2
+];
+
+/// Moniteur ambre.
+pub const AMBER_PALETTE: Palette = [
+    [0x14, 0x0C, 0x00],
+    [0xFF, 0xB0, 0x00],
+    [0x99, 0x66, 0x00],
+    [0xFF, 0xD8, 0x80],
+];
+
+/// Phosphore vert P1.
+pub const GREEN_PALETTE: Palette = [
+    [0x00, 0x14, 0x00],
+    [0x33, 0xFF, 0x33],
+    [0x11, 0x88, 0x11],
+    [0xAA, 0xFF, 0xAA],
+];
+
+/// Écran LCD vert olive, pixels sombres sur fond clair.
+pub const LCD_PALETTE: Palette = [
+    [0x9B, 0xBC, 0x0F],
+    [0x0F, 0x38, 0x0F],
+    [0x8B, 0xAC, 0x0F],
+    [0x30, 0x62, 0x30],
+];
+
+/// Couleurs par défaut d'Octo.
+pub const OCTO_PALETTE: Palette = [
+    [0x99, 0x66, 0x00],
+    [0xFF, 0xCC, 0x00],
+    [0xFF, 0x66, 0x00],
+    [0x66, 0x22, 0x00],
+];
+
+/// Préréglages acceptés par `--palette` et la configuration.
+pub const PALETTE_PRESETS: [(&str, Palette); 5] = [
+    ("mono", DEFAULT_PALETTE),
+    ("amber", AMBER_PALETTE),
+    ("green", GREEN_PALETTE),
+    ("lcd", LCD_PALETTE),
+    ("octo", OCTO_PALETTE),
+];
+
+/// Mise à l'échelle de l'écran logique dans une fenêtre de taille quelconque,
+/// avec des bandes noires autour.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Scaling {
+    /// Plus grand multiple entier de la résolution : pixels tous de la même taille.
+    #[default]
+    Integer,
+    /// Toute la place possible en gardant les proportions.
+    Aspect,
+}
+
+/// Zone de la fenêtre où `cols` x `rows` pixels logiques sont dessinés.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Viewport {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+    pub cols: u32,
+    pub rows: u32,
 }
 
-pub struct ContextPixels<'a> {
-    pub screen: Canvas<Window>,
-    pub textures: [Texture<'a>; 2],
-    pub pixel: [[Pixel; H as usize]; W as usize],
-    pub keyboard: Keyboard,
+/// Écran logique de la machine, indépendant de tout backend de rendu.
+/// Chaque case contient la valeur du pixel, un bit par plan (`BLACK`, `WHITE` hors XO-CHIP).
+/// Le tableau est dimensionné pour la haute résolution, seule la zone `width()` x `height()`
+/// est active.
+#[derive(Clone)]
+pub struct FrameBuffer {
+    pub pixel: [[u8; HIRES_H as usize]; HIRES_W as usize],
+    pub hires: bool,
+    pub planes: u8, // masque des plans sélectionnés par FN01
 }
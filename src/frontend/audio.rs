@@ -0,0 +1,76 @@
+use sdl2::{
+    audio::{AudioCallback, AudioSpecDesired},
+    AudioSubsystem,
+};
+
+use chip_8::audio::schema::{AudioOutput, PatternWave, SquareWave, Tone, SAMPLE_RATE};
+
+use super::schema::{Beeper, SdlAudio};
+
+impl AudioCallback for Beeper {
+    type Channel = f32;
+
+    fn callback(&mut self, out: &mut [f32]) {
+        match &mut self.pattern {
+            Some(pattern) => pattern.fill(out),
+            None => self.square.fill(out),
+        }
+    }
+}
+
+impl SdlAudio {
+    pub fn open(audio: &AudioSubsystem, frequency: f32, volume: f32) -> Result<Self, String> {
+        let desired = AudioSpecDesired {
+            freq: Some(SAMPLE_RATE),
+            channels: Some(1),
+            samples: None,
+        };
+
+        let device = audio.open_playback(None, &desired, |spec| Beeper {
+            square: SquareWave::new(frequency, volume, spec.freq),
+            pattern: None,
+            sample_rate: spec.freq,
+        })?;
+
+        Ok(SdlAudio {
+            device,
+            playing: false,
+            tone: Tone::Beep,
+        })
+    }
+}
+
+impl AudioOutput for SdlAudio {
+    fn set_beep(&mut self, active: bool) {
+        if active == self.playing {
+            return;
+        }
+        if active {
+            self.device.resume();
+        } else {
+            self.device.pause();
+        }
+        self.playing = active;
+    }
+
+    fn set_tone(&mut self, tone: Tone) {
+        if tone == self.tone {
+            return;
+        }
+        let mut beeper = self.device.lock();
+        beeper.pattern = match tone {
+            Tone::Beep => None,
+            // un changement de hauteur garde la position dans le motif
+            Tone::Pattern { bits, rate } => Some(match beeper.pattern {
+                Some(pattern) => PatternWave {
+                    bits,
+                    phase_inc: rate / beeper.sample_rate as f32,
+                    ..pattern
+                },
+                None => PatternWave::new(bits, rate, beeper.square.volume, beeper.sample_rate),
+            }),
+        };
+        drop(beeper);
+        self.tone = tone;
+    }
+}
@@ -0,0 +1,60 @@
+use std::io::{self, BufRead, Write};
+
+use chip_8::debugger::schema::{Action, Command, Debugger};
+use chip_8::machine::schema::Machine;
+
+use crate::Config;
+
+/// Lit et exécute une commande sur l'entrée standard.
+/// Une fin de fichier équivaut à `quit`.
+pub fn prompt(debugger: &mut Debugger, machine: &mut Machine) -> Action {
+    print!("(chip8) ");
+    let _ = io::stdout().flush();
+
+    let mut line = String::new();
+    match io::stdin().lock().read_line(&mut line) {
+        Ok(0) | Err(_) => return Action::Quit,
+        Ok(_) => {}
+    }
+
+    match line.parse::<Command>() {
+        Ok(command) => {
+            let (action, output) = debugger.execute(command, machine);
+            print_events(debugger);
+            if !output.is_empty() {
+                println!("{}", output.trim_end());
+            }
+            action
+        }
+        Err(e) => {
+            println!("{}", e);
+            Action::Paused
+        }
+    }
+}
+
+/// Annonce l'arrêt et affiche l'état de la machine.
+pub fn report_break(debugger: &mut Debugger, machine: &Machine, reason: &str) {
+    print_events(debugger);
+    println!("{}", reason);
+    print!("{}", Debugger::state(machine));
+}
+
+/// Affiche les déclenchements des points de surveillance.
+pub fn print_events(debugger: &mut Debugger) {
+    for event in debugger.events.drain(..) {
+        println!("{}", event);
+    }
+}
+
+/// Crée le débogueur demandé par `--debugger` ou `--watch`.
+pub fn attach(config: &Config, machine: &Machine) -> Option<Debugger> {
+    if !config.debugger && config.watch.is_empty() {
+        return None;
+    }
+    let mut debugger = Debugger::new(config.debugger);
+    for watch in &config.watch {
+        debugger.add_watch(*watch, machine);
+    }
+    Some(debugger)
+}
@@ -0,0 +1,109 @@
+use sdl2::{
+    controller::{Axis as SdlAxis, Button as SdlButton},
+    event::Event,
+    GameControllerSubsystem,
+};
+
+use chip_8::input::schema::{Axis, Button, InputEvent};
+
+use super::schema::{Gamepads, HostLayout};
+
+impl Gamepads {
+    pub fn new(subsystem: GameControllerSubsystem) -> Self {
+        Gamepads {
+            subsystem,
+            players: Default::default(),
+        }
+    }
+
+    /// Ouvre la manette `index` à la première place libre et renvoie le joueur.
+    /// Les manettes déjà présentes au lancement arrivent aussi par cet évènement.
+    pub fn connect(&mut self, index: u32) -> Option<u8> {
+        let slot = self.players.iter().position(Option::is_none)?;
+        let controller = self.subsystem.open(index).ok()?;
+        println!("Gamepad '{}' is player {}", controller.name(), slot + 1);
+        self.players[slot] = Some(controller);
+        Some(slot as u8)
+    }
+
+    /// Libère la place de la manette débranchée et renvoie son joueur.
+    pub fn disconnect(&mut self, instance_id: u32) -> Option<u8> {
+        let player = self.player(instance_id)?;
+        if let Some(controller) = self.players[player as usize].take() {
+            println!("Gamepad '{}' disconnected", controller.name());
+        }
+        Some(player)
+    }
+
+    fn player(&self, instance_id: u32) -> Option<u8> {
+        self.players
+            .iter()
+            .position(|c| c.as_ref().is_some_and(|c| c.instance_id() == instance_id))
+            .map(|slot| slot as u8)
+    }
+
+    /// Traduit un évènement clavier ou manette SDL en évènement du pavé.
+    pub fn translate(&self, event: &Event, layout: &HostLayout) -> Option<InputEvent> {
+        match *event {
+            Event::KeyDown {
+                keycode: Some(keycode),
+                ..
+            }
+            | Event::KeyUp {
+                keycode: Some(keycode),
+                ..
+            } => Some(InputEvent::Key {
+                code: keycode as i32,
+                key: layout.pad_key(keycode)?,
+                pressed: matches!(event, Event::KeyDown { .. }),
+            }),
+            Event::ControllerButtonDown { which, button, .. }
+            | Event::ControllerButtonUp { which, button, .. } => Some(InputEvent::Button {
+                player: self.player(which)?,
+                button: button_from_sdl(button)?,
+                pressed: matches!(event, Event::ControllerButtonDown { .. }),
+            }),
+            Event::ControllerAxisMotion {
+                which, axis, value, ..
+            } => Some(InputEvent::Axis {
+                player: self.player(which)?,
+                axis: axis_from_sdl(axis),
+                value,
+            }),
+            _ => None,
+        }
+    }
+}
+
+fn button_from_sdl(button: SdlButton) -> Option<Button> {
+    Some(match button {
+        SdlButton::A => Button::A,
+        SdlButton::B => Button::B,
+        SdlButton::X => Button::X,
+        SdlButton::Y => Button::Y,
+        SdlButton::Back => Button::Back,
+        SdlButton::Guide => Button::Guide,
+        SdlButton::Start => Button::Start,
+        SdlButton::LeftStick => Button::LeftStick,
+        SdlButton::RightStick => Button::RightStick,
+        SdlButton::LeftShoulder => Button::LeftShoulder,
+        SdlButton::RightShoulder => Button::RightShoulder,
+        SdlButton::DPadUp => Button::DPadUp,
+        SdlButton::DPadDown => Button::DPadDown,
+        SdlButton::DPadLeft => Button::DPadLeft,
+        SdlButton::DPadRight => Button::DPadRight,
+        // palettes et pavé tactile n'existent pas sur toutes les manettes
+        _ => return None,
+    })
+}
+
+fn axis_from_sdl(axis: SdlAxis) -> Axis {
+    match axis {
+        SdlAxis::LeftX => Axis::LeftX,
+        SdlAxis::LeftY => Axis::LeftY,
+        SdlAxis::RightX => Axis::RightX,
+        SdlAxis::RightY => Axis::RightY,
+        SdlAxis::TriggerLeft => Axis::TriggerLeft,
+        SdlAxis::TriggerRight => Axis::TriggerRight,
+    }
+}
@@ -0,0 +1,147 @@
+use std::{fs, path::Path};
+
+use chip_8::cpu::schema::Chip8Error;
+use chip_8::debugger::schema::{Action, Debugger};
+use chip_8::display::schema::Palette;
+use chip_8::machine::schema::Machine;
+use chip_8::movie::schema::{Movie, Session};
+
+use super::debugger::{attach, print_events, prompt, report_break};
+use crate::Config;
+
+/// Exécute la ROM sans fenêtre puis exporte l'écran final.
+pub fn run_headless(
+    machine: &mut Machine,
+    config: &Config,
+    mut session: Option<Session>,
+    palette: &Palette,
+) -> Result<(), String> {
+    let result = match (attach(config, machine), session.as_mut()) {
+        (Some(debugger), _) => run_debugger(machine, config, debugger),
+        (None, Some(session)) => run_session(machine, config, session),
+        (None, None) => machine.run_until(config.speed, config.frames, config.until),
+    };
+    if let Ok(frames) = result {
+        println!("Ran {} frame(s)", frames);
+    }
+
+    // le film et l'écran sont écrits même après une faute, pour aider au diagnostic
+    if let (Some(Session::Record(movie)), Some(path)) = (&session, &config.record) {
+        save_movie(movie, path)?;
+    }
+    dump_screen(machine, config, palette)?;
+    result
+        .map(|_| ())
+        .map_err(|e| format!("emulation stopped: {}", e))
+}
+
+/// Boucle du débogueur sans fenêtre : `continue` reste borné par `--frames`.
+/// Avec seulement des `--watch ... log`, la ROM tourne jusqu'au bout sans invite
+/// et une faute arrête l'émulation comme sans débogueur.
+fn run_debugger(
+    machine: &mut Machine,
+    config: &Config,
+    mut debugger: Debugger,
+) -> Result<u32, Chip8Error> {
+    let mut frames = 0;
+    if debugger.paused {
+        report_break(
+            &mut debugger,
+            machine,
+            "Paused at start, type 'help' for commands",
+        );
+    }
+
+    loop {
+        if debugger.paused {
+            if prompt(&mut debugger, machine) == Action::Quit {
+                return Ok(frames);
+            }
+            continue;
+        }
+        let stopped = config.until.is_some_and(|until| machine.is_stopped(until));
+        if frames >= config.frames || machine.cpu.exited || stopped {
+            if !config.debugger {
+                print_events(&mut debugger);
+                return Ok(frames);
+            }
+            debugger.paused = true;
+            report_break(&mut debugger, machine, "Program stopped");
+            continue;
+        }
+
+        match machine.run_frame_until(config.speed, &mut |m| debugger.should_break(m)) {
+            Ok(true) => report_break(&mut debugger, machine, "Break"),
+            Ok(false) => {
+                print_events(&mut debugger);
+                frames += 1;
+            }
+            Err(e) if !config.debugger => {
+                print_events(&mut debugger);
+                return Err(e);
+            }
+            Err(e) => {
+                debugger.paused = true;
+                report_break(&mut debugger, machine, &e.to_string());
+            }
+        }
+    }
+}
+
+/// Enregistre au plus `--frames` frames, ou rejoue le film en entier.
+/// `--until` n'est vérifié qu'entre deux frames pour que la relecture reste exacte.
+fn run_session(
+    machine: &mut Machine,
+    config: &Config,
+    session: &mut Session,
+) -> Result<u32, Chip8Error> {
+    let limit = match session {
+        Session::Record(_) => config.frames,
+        Session::Replay { .. } => u32::MAX,
+    };
+    let mut frames = 0;
+    while frames < limit && !machine.cpu.exited {
+        if config.until.is_some_and(|until| machine.is_stopped(until)) {
+            break;
+        }
+        if !session.run_frame(machine)? {
+            break;
+        }
+        frames += 1;
+    }
+    Ok(frames)
+}
+
+pub fn save_movie(movie: &Movie, path: &Path) -> Result<(), String> {
+    movie
+        .save(path)
+        .map_err(|e| format!("cannot write movie {}: {}", path.display(), e))?;
+    println!(
+        "Recorded {} frame(s) to {}",
+        movie.frames.len(),
+        path.display()
+    );
+    Ok(())
+}
+
+fn dump_screen(machine: &Machine, config: &Config, palette: &Palette) -> Result<(), String> {
+    let Some(path) = &config.dump else {
+        print!("{}", machine.screen.to_text());
+        return Ok(());
+    };
+
+    let path = Path::new(path);
+    let ext = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_ascii_lowercase());
+    let res = match ext.as_deref() {
+        Some("pbm") | Some("png") => {
+            machine
+                .screen
+                .save_image(path, palette, config.screenshot_scale)
+        }
+        _ => fs::write(path, machine.screen.to_text()),
+    };
+    res.map_err(|e| format!("cannot write {}: {}", path.display(), e))
+}
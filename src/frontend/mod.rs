@@ -0,0 +1,12 @@
+#[cfg(feature = "sdl")]
+pub mod audio;
+pub mod debugger;
+#[cfg(feature = "sdl")]
+pub mod gamepad;
+pub mod headless;
+#[cfg(feature = "sdl")]
+pub mod schema;
+#[cfg(feature = "sdl")]
+pub mod sdl;
+#[cfg(feature = "sdl")]
+pub mod video;
@@ -0,0 +1,58 @@
+use std::collections::HashMap;
+
+use sdl2::audio::AudioDevice;
+use sdl2::controller::GameController;
+use sdl2::keyboard::Keycode;
+use sdl2::render::{Canvas, Texture};
+use sdl2::video::Window;
+use sdl2::GameControllerSubsystem;
+
+use chip_8::audio::schema::{PatternWave, SquareWave, Tone};
+use chip_8::display::schema::{Palette, Scaling};
+use chip_8::input::schema::MAX_PLAYERS;
+use chip_8::video::schema::FrameSink;
+
+pub struct ContextPixels<'a> {
+    pub screen: Canvas<Window>,
+    pub textures: [Texture<'a>; 4],
+    pub scaling: Scaling,
+}
+
+/// Callback SDL qui joue le motif XO-CHIP s'il y en a un, l'onde carrée du bip sinon.
+pub struct Beeper {
+    pub square: SquareWave,
+    pub pattern: Option<PatternWave>,
+    pub sample_rate: i32,
+}
+
+/// Sortie audio SDL : le périphérique est mis en pause quand le bip s'arrête.
+pub struct SdlAudio {
+    pub device: AudioDevice<Beeper>,
+    pub playing: bool,
+    pub tone: Tone,
+}
+
+/// Touches du clavier hôte vers les touches du pavé CHIP-8.
+pub struct HostLayout {
+    pub keys: HashMap<Keycode, u8>,
+}
+
+/// Manettes ouvertes, le joueur est la place prise au branchement.
+pub struct Gamepads {
+    pub subsystem: GameControllerSubsystem,
+    pub players: [Option<GameController>; MAX_PLAYERS],
+}
+
+/// Raccourcis de sauvegarde rapide, l'emplacement va de 1 à `STATE_SLOTS`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StateHotkey {
+    Save(u8),
+    Load(u8),
+}
+
+/// Enregistrements vidéo en cours, alimentés à chaque frame présentée.
+pub struct VideoCapture {
+    pub sinks: Vec<Box<dyn FrameSink>>,
+    pub palette: Palette,
+    pub scale: u32,
+}
@@ -0,0 +1,221 @@
+use std::{collections::HashMap, path::PathBuf};
+
+use sdl2::{
+    event::Event,
+    keyboard::{Keycode, Mod},
+    pixels::{Color, PixelFormatEnum},
+    rect::Rect,
+    render::{Canvas, TextureCreator},
+    surface::Surface,
+    video::{FullscreenType, Window, WindowContext},
+};
+
+use chip_8::display::schema::{FrameBuffer, Palette, Scaling};
+use chip_8::machine::schema::Machine;
+use chip_8::rom::schema::STDIN_PATH;
+use chip_8::state::state::slot_path;
+
+use crate::config::schema::KeyboardConfig;
+
+use super::schema::{ContextPixels, HostLayout, StateHotkey};
+
+impl<'a> ContextPixels<'a> {
+    pub fn init(
+        screen: Canvas<Window>,
+        texture_creator: &'a TextureCreator<WindowContext>,
+        palette: &Palette,
+        scaling: Scaling,
+    ) -> Self {
+        // une texture unie par couleur de la palette, indexée par la valeur du pixel,
+        // étirée à la taille du pixel au moment de la copie
+        let textures = palette.map(|[r, g, b]| {
+            let mut surface = Surface::new(1, 1, PixelFormatEnum::RGB24).unwrap();
+            surface.fill_rect(None, Color::RGB(r, g, b)).unwrap();
+            texture_creator
+                .create_texture_from_surface(&surface)
+                .expect("Erreur texture palette")
+        });
+
+        Self {
+            screen,
+            textures,
+            scaling,
+        }
+    }
+
+    /// Dessine un pixel logique dans le rectangle `position` de la fenêtre.
+    pub fn draw_pixel(&mut self, position: Rect, color: u8) {
+        let texture = &self.textures[color as usize];
+
+        self.screen
+            .copy(texture, None, Some(position))
+            .expect("error during pixel render");
+    }
+
+    pub fn update_screen(&mut self, fb: &FrameBuffer) {
+        // la fenêtre garde sa taille, les pixels rapetissent en haute résolution
+        let output = self
+            .screen
+            .output_size()
+            .expect("error reading window size");
+        let view = self.scaling.viewport(output, (fb.width(), fb.height()));
+
+        // bandes noires autour de l'image quand la fenêtre n'a pas ses proportions
+        self.screen.set_draw_color(Color::BLACK);
+        self.screen.clear();
+        for x in 0..view.cols {
+            for y in 0..view.rows {
+                let (left, top, width, height) = view.cell(x, y);
+                let position = Rect::new(left, top, width, height);
+                self.draw_pixel(position, fb.pixel[x as usize][y as usize]);
+            }
+        }
+        self.screen.present();
+    }
+
+    /// Passe du plein écran (à la résolution du bureau) au fenêtré et inversement.
+    pub fn toggle_fullscreen(&mut self) {
+        let window = self.screen.window_mut();
+        let mode = match window.fullscreen_state() {
+            FullscreenType::Off => FullscreenType::Desktop,
+            _ => FullscreenType::Off,
+        };
+        if let Err(e) = window.set_fullscreen(mode) {
+            eprintln!("Cannot toggle fullscreen: {}", e);
+        }
+    }
+}
+
+impl HostLayout {
+    /// Résout les noms de touches SDL (`"W"`, `"é"`, `"Keypad 8"`) de la configuration.
+    pub fn from_config(config: &KeyboardConfig) -> Result<Self, String> {
+        let mut keys = HashMap::new();
+        for (name, chip8_key) in config.bindings()? {
+            let keycode = Keycode::from_name(&name)
+                .ok_or_else(|| format!("unknown key name '{}' in keyboard config", name))?;
+            keys.insert(keycode, chip8_key);
+        }
+        Ok(Self { keys })
+    }
+
+    pub fn pad_key(&self, keycode: Keycode) -> Option<u8> {
+        self.keys.get(&keycode).copied()
+    }
+}
+
+/// F1-F4 sauvegardent dans les emplacements 1 à 4, F5-F8 les rechargent.
+pub fn state_hotkey(event: &Event) -> Option<StateHotkey> {
+    let Event::KeyDown {
+        keycode: Some(keycode),
+        repeat: false,
+        ..
+    } = *event
+    else {
+        return None;
+    };
+    match keycode {
+        Keycode::F1 => Some(StateHotkey::Save(1)),
+        Keycode::F2 => Some(StateHotkey::Save(2)),
+        Keycode::F3 => Some(StateHotkey::Save(3)),
+        Keycode::F4 => Some(StateHotkey::Save(4)),
+        Keycode::F5 => Some(StateHotkey::Load(1)),
+        Keycode::F6 => Some(StateHotkey::Load(2)),
+        Keycode::F7 => Some(StateHotkey::Load(3)),
+        Keycode::F8 => Some(StateHotkey::Load(4)),
+        _ => None,
+    }
+}
+
+/// Sauvegarde ou recharge un emplacement ; un échec est signalé sans arrêter le jeu.
+pub fn quick_state(machine: &mut Machine, rom_path: &str, hotkey: StateHotkey) {
+    let slot = match hotkey {
+        StateHotkey::Save(slot) | StateHotkey::Load(slot) => slot,
+    };
+    let Some(path) = slot_path(rom_path, slot) else {
+        eprintln!("Save states need a ROM file, not standard input");
+        return;
+    };
+    let result = match hotkey {
+        StateHotkey::Save(_) => machine.save_state_file(&path).map_err(|e| e.to_string()),
+        StateHotkey::Load(_) => machine.load_state_file(&path).map_err(|e| e.to_string()),
+    };
+    match (result, hotkey) {
+        (Ok(()), StateHotkey::Save(_)) => println!("State saved to {}", path.display()),
+        (Ok(()), StateHotkey::Load(_)) => println!("State loaded from {}", path.display()),
+        (Err(e), _) => eprintln!("Slot {} ({}): {}", slot, path.display(), e),
+    }
+}
+
+/// F9 capture l'écran en PNG, Maj+F9 en PBM ; retourne l'extension du fichier.
+pub fn screenshot_hotkey(event: &Event) -> Option<&'static str> {
+    match *event {
+        Event::KeyDown {
+            keycode: Some(Keycode::F9),
+            keymod,
+            repeat: false,
+            ..
+        } => Some(if keymod.intersects(Mod::LSHIFTMOD | Mod::RSHIFTMOD) {
+            "pbm"
+        } else {
+            "png"
+        }),
+        _ => None,
+    }
+}
+
+/// Premier nom libre du dossier courant pour une capture de la ROM :
+/// `BRIX-001.png`, `BRIX-002.png`...
+pub fn next_capture_path(rom_path: &str, ext: &str) -> Option<PathBuf> {
+    let stem = match rom_path {
+        STDIN_PATH => "chip8".to_string(),
+        path => PathBuf::from(path)
+            .file_stem()
+            .map_or("chip8".into(), |s| s.to_string_lossy().into_owned()),
+    };
+    (1..1000)
+        .map(|n| PathBuf::from(format!("{}-{:03}.{}", stem, n, ext)))
+        .find(|p| !p.exists())
+}
+
+/// Enregistre l'écran logique dans le dossier courant, sous le premier nom libre.
+pub fn take_screenshot(
+    screen: &FrameBuffer,
+    rom_path: &str,
+    ext: &str,
+    palette: &Palette,
+    scale: u32,
+) {
+    let Some(path) = next_capture_path(rom_path, ext) else {
+        eprintln!("No free screenshot name left in the current directory");
+        return;
+    };
+    match screen.save_image(&path, palette, scale) {
+        Ok(()) => println!("Screenshot saved to {}", path.display()),
+        Err(e) => eprintln!("cannot write {}: {}", path.display(), e),
+    }
+}
+
+/// Alt+Entrée bascule le plein écran.
+pub fn fullscreen_hotkey(event: &Event) -> bool {
+    matches!(
+        event,
+        Event::KeyDown {
+            keycode: Some(Keycode::Return | Keycode::KpEnter),
+            keymod,
+            repeat: false,
+            ..
+        } if keymod.intersects(Mod::LALTMOD | Mod::RALTMOD)
+    )
+}
+
+/// F10 démarre ou arrête l'enregistrement vidéo.
+pub fn video_hotkey(event: &Event) -> bool {
+    matches!(
+        event,
+        Event::KeyDown {
+            keycode: Some(Keycode::F10),
+            repeat: false,
+            ..
+        }
+    )
+}
@@ -0,0 +1,84 @@
+use std::path::Path;
+
+use chip_8::display::schema::{FrameBuffer, Palette};
+use chip_8::video::schema::{GifRecorder, RawRecorder};
+
+use super::schema::VideoCapture;
+use super::sdl::next_capture_path;
+use crate::Config;
+
+impl VideoCapture {
+    /// Ouvre les fichiers de `--record-video` et `--record-raw`, l'enregistrement
+    /// commence alors dès le lancement.
+    pub fn from_config(config: &Config, palette: &Palette) -> Result<Self, String> {
+        let mut capture = VideoCapture {
+            sinks: Vec::new(),
+            palette: *palette,
+            scale: config.video_scale,
+        };
+        if let Some(path) = &config.record_video {
+            capture.start_gif(path)?;
+        }
+        if let Some(path) = &config.record_raw {
+            let raw = RawRecorder::create(path, palette)
+                .map_err(|e| format!("cannot create {}: {}", path.display(), e))?;
+            capture.sinks.push(Box::new(raw));
+        }
+        Ok(capture)
+    }
+
+    fn start_gif(&mut self, path: &Path) -> Result<(), String> {
+        let gif = GifRecorder::create(path, &self.palette, self.scale)
+            .map_err(|e| format!("cannot create {}: {}", path.display(), e))?;
+        println!("Recording video to {}", path.display());
+        self.sinks.push(Box::new(gif));
+        Ok(())
+    }
+
+    /// Arrête les enregistrements en cours, ou commence un GIF numéroté s'il n'y en a pas.
+    pub fn toggle(&mut self, rom_path: &str) {
+        if !self.sinks.is_empty() {
+            self.finish();
+            return;
+        }
+        let result = next_capture_path(rom_path, "gif")
+            .ok_or_else(|| "no free video name left in the current directory".to_string())
+            .and_then(|path| self.start_gif(&path));
+        if let Err(e) = result {
+            eprintln!("{}", e);
+        }
+    }
+
+    /// Ajoute une frame présentée qui reste affichée `ticks` frames. Un enregistrement
+    /// qui échoue est arrêté sans interrompre le jeu.
+    pub fn push(&mut self, screen: &FrameBuffer, ticks: u32) {
+        if self.sinks.is_empty() || ticks == 0 {
+            return;
+        }
+        let pixels = screen.to_indexed(self.scale);
+        self.sinks
+            .retain_mut(|sink| match sink.push(&pixels, ticks) {
+                Ok(()) => true,
+                Err(e) => {
+                    eprintln!("video {} stopped: {}", sink.path().display(), e);
+                    false
+                }
+            });
+    }
+
+    pub fn finish(&mut self) {
+        for mut sink in self.sinks.drain(..) {
+            match sink.finish() {
+                Ok(()) => println!("Video saved to {}", sink.path().display()),
+                Err(e) => eprintln!("cannot write {}: {}", sink.path().display(), e),
+            }
+        }
+    }
+}
+
+// un enregistrement interrompu par une erreur de la boucle reste lisible
+impl Drop for VideoCapture {
+    fn drop(&mut self) {
+        self.finish();
+    }
+}
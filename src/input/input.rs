@@ -0,0 +1,173 @@
+use std::str::FromStr;
+
+use crate::cpu::schema::Keyboard;
+
+use super::schema::{
+    Axis, Button, Control, GamepadMapping, Input, InputEvent, Source, DEFAULT_THRESHOLD,
+    MAX_PLAYERS,
+};
+
+impl Input {
+    pub fn new(mappings: [GamepadMapping; MAX_PLAYERS], threshold: i16) -> Self {
+        Input {
+            mappings,
+            threshold,
+            held: Default::default(),
+        }
+    }
+
+    /// Applique un évènement au pavé émulé.
+    pub fn handle(&mut self, event: InputEvent, keyboard: &mut Keyboard) {
+        match event {
+            InputEvent::Key { code, key, pressed } => {
+                self.set(Source::Key(code), pressed.then_some(key), keyboard)
+            }
+            InputEvent::Button {
+                player,
+                button,
+                pressed,
+            } => {
+                let control = Control::Button(button);
+                let key = self.binding(player, control).filter(|_| pressed);
+                self.set(Source::Gamepad { player, control }, key, keyboard);
+            }
+            InputEvent::Axis {
+                player,
+                axis,
+                value,
+            } => {
+                for positive in [false, true] {
+                    let active = if positive {
+                        value > self.threshold
+                    } else {
+                        value < -self.threshold
+                    };
+                    let control = Control::Axis { axis, positive };
+                    let key = self.binding(player, control).filter(|_| active);
+                    self.set(Source::Gamepad { player, control }, key, keyboard);
+                }
+            }
+        }
+    }
+
+    /// Relâche tout ce que tenait une manette débranchée.
+    pub fn release_player(&mut self, player: u8, keyboard: &mut Keyboard) {
+        let sources: Vec<Source> = self
+            .held
+            .keys()
+            .filter(|s| matches!(s, Source::Gamepad { player: p, .. } if *p == player))
+            .copied()
+            .collect();
+        for source in sources {
+            self.set(source, None, keyboard);
+        }
+    }
+
+    fn binding(&self, player: u8, control: Control) -> Option<u8> {
+        let mapping = self.mappings.get(player as usize)?;
+        mapping.bindings.get(&control).copied()
+    }
+
+    fn set(&mut self, source: Source, key: Option<u8>, keyboard: &mut Keyboard) {
+        let previous = match key {
+            Some(key) => self.held.insert(source, key),
+            None => self.held.remove(&source),
+        };
+        for key in [previous, key].into_iter().flatten() {
+            let down = self.held.values().any(|&held| held == key);
+            keyboard.set_key(key, down);
+        }
+    }
+}
+
+impl Default for Input {
+    fn default() -> Self {
+        Self::new(Default::default(), DEFAULT_THRESHOLD)
+    }
+}
+
+impl GamepadMapping {
+    /// Croix et stick gauche sur 5/8/7/9, A sur 6 et B sur 4, comme W/S/A/D, E et Q au clavier.
+    pub fn standard() -> Self {
+        let pairs = [
+            ("dpup", 0x5),
+            ("dpdown", 0x8),
+            ("dpleft", 0x7),
+            ("dpright", 0x9),
+            ("lefty-", 0x5),
+            ("lefty+", 0x8),
+            ("leftx-", 0x7),
+            ("leftx+", 0x9),
+            ("a", 0x6),
+            ("b", 0x4),
+        ];
+        GamepadMapping {
+            bindings: pairs
+                .iter()
+                .filter_map(|(name, key)| Some((name.parse().ok()?, *key)))
+                .collect(),
+        }
+    }
+}
+
+impl FromStr for Button {
+    type Err = String;
+
+    /// Noms des chaînes de mapping SDL (`a`, `start`, `dpup`...).
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "a" => Ok(Button::A),
+            "b" => Ok(Button::B),
+            "x" => Ok(Button::X),
+            "y" => Ok(Button::Y),
+            "back" => Ok(Button::Back),
+            "guide" => Ok(Button::Guide),
+            "start" => Ok(Button::Start),
+            "leftstick" => Ok(Button::LeftStick),
+            "rightstick" => Ok(Button::RightStick),
+            "leftshoulder" => Ok(Button::LeftShoulder),
+            "rightshoulder" => Ok(Button::RightShoulder),
+            "dpup" => Ok(Button::DPadUp),
+            "dpdown" => Ok(Button::DPadDown),
+            "dpleft" => Ok(Button::DPadLeft),
+            "dpright" => Ok(Button::DPadRight),
+            _ => Err(format!("unknown gamepad button '{}'", s)),
+        }
+    }
+}
+
+impl FromStr for Axis {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "leftx" => Ok(Axis::LeftX),
+            "lefty" => Ok(Axis::LeftY),
+            "rightx" => Ok(Axis::RightX),
+            "righty" => Ok(Axis::RightY),
+            "lefttrigger" => Ok(Axis::TriggerLeft),
+            "righttrigger" => Ok(Axis::TriggerRight),
+            _ => Err(format!("unknown gamepad axis '{}'", s)),
+        }
+    }
+}
+
+/// Un bouton (`a`, `dpup`), ou un axe suivi de `+`/`-` (`leftx-`). Les gâchettes
+/// ne vont que dans un sens, `lefttrigger` suffit.
+impl FromStr for Control {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Ok(button) = s.parse() {
+            return Ok(Control::Button(button));
+        }
+        let (name, positive) = match s.strip_suffix('-') {
+            Some(name) => (name, false),
+            None => (s.strip_suffix('+').unwrap_or(s), true),
+        };
+        let axis = name
+            .parse()
+            .map_err(|_| format!("unknown gamepad control '{}'", s))?;
+        Ok(Control::Axis { axis, positive })
+    }
+}
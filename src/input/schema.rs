@@ -0,0 +1,85 @@
+use std::collections::HashMap;
+
+pub const MAX_PLAYERS: usize = 2; // manettes prises en compte, dans l'ordre de branchement
+pub const DEFAULT_THRESHOLD: i16 = 16384; // un stick compte à mi-course
+
+/// Boutons d'une manette au format SDL GameController.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Button {
+    A,
+    B,
+    X,
+    Y,
+    Back,
+    Guide,
+    Start,
+    LeftStick,
+    RightStick,
+    LeftShoulder,
+    RightShoulder,
+    DPadUp,
+    DPadDown,
+    DPadLeft,
+    DPadRight,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Axis {
+    LeftX,
+    LeftY,
+    RightX,
+    RightY,
+    TriggerLeft,
+    TriggerRight,
+}
+
+/// Élément de manette associable à une touche du pavé : un bouton,
+/// ou un axe poussé au-delà du seuil dans un sens.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Control {
+    Button(Button),
+    Axis { axis: Axis, positive: bool },
+}
+
+/// Évènement d'entrée indépendant de SDL, que le frontend traduit et que les tests
+/// peuvent fabriquer directement.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum InputEvent {
+    /// Touche hôte `code` déjà traduite en touche du pavé.
+    Key {
+        code: i32,
+        key: u8,
+        pressed: bool,
+    },
+    Button {
+        player: u8,
+        button: Button,
+        pressed: bool,
+    },
+    Axis {
+        player: u8,
+        axis: Axis,
+        value: i16,
+    },
+}
+
+/// Origine d'un appui, pour qu'une touche reste enfoncée tant qu'une source la tient.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Source {
+    Key(i32),
+    Gamepad { player: u8, control: Control },
+}
+
+/// Contrôles d'une manette vers les touches du pavé.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct GamepadMapping {
+    pub bindings: HashMap<Control, u8>,
+}
+
+/// Combine le clavier et les manettes avant le `Keyboard` émulé.
+#[derive(Clone, Debug)]
+pub struct Input {
+    pub mappings: [GamepadMapping; MAX_PLAYERS],
+    pub threshold: i16,
+    pub held: HashMap<Source, u8>,
+}
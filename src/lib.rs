@@ -0,0 +1,14 @@
+pub mod asm;
+pub mod audio;
+pub mod cpu;
+pub mod debugger;
+pub mod disasm;
+pub mod display;
+pub mod input;
+pub mod machine;
+pub mod movie;
+pub mod rewind;
+pub mod rom;
+pub mod scheduler;
+pub mod state;
+pub mod video;
@@ -0,0 +1,221 @@
+use std::str::FromStr;
+
+use crate::audio::schema::{AudioOutput, NullAudio};
+use crate::cpu::schema::{Chip8Error, DecodeCache, Instruction, Keyboard, Quirks, CPU, START_ADRR};
+use crate::display::schema::FrameBuffer;
+use crate::rom::loader::read_rom;
+use crate::rom::schema::LoadError;
+use crate::scheduler::schema::Slice;
+use crate::state::state::rom_hash;
+
+use super::schema::{ErrorPolicy, Machine, StopCondition};
+
+impl Machine {
+    pub fn new(debug: bool, quirks: Quirks) -> Self {
+        let mut cpu = CPU::new(debug, quirks);
+        cpu.init_memory(); //mapper la police
+
+        Machine {
+            cpu,
+            screen: FrameBuffer::new(),
+            keyboard: Keyboard::new(),
+            cache: None,
+            audio: Box::new(NullAudio),
+            on_error: ErrorPolicy::Halt,
+            rom_hash: rom_hash(&[]),
+        }
+    }
+
+    /// Active le cache de décodage et prédécode la mémoire programme.
+    pub fn enable_decode_cache(&mut self) {
+        let mut cache = DecodeCache::new(self.cpu.mem.len());
+        cache.predecode(&self.cpu.mem, START_ADRR, self.cpu.mem.len());
+        self.cache = Some(cache);
+    }
+
+    pub fn set_audio(&mut self, audio: Box<dyn AudioOutput>) {
+        self.audio = audio;
+    }
+
+    /// Décrémente les timers et fait jouer le bip tant que le timer sonore est actif.
+    pub fn tick_timers(&mut self) {
+        self.cpu.countdown();
+        self.audio.set_tone(self.cpu.tone());
+        self.audio.set_beep(self.cpu.sound_count > 0);
+    }
+
+    /// Charge une ROM depuis un fichier, une archive gzip/zip ou l'entrée standard (`-`).
+    pub fn load_game(&mut self, path: &str, addr: usize) -> Result<(), LoadError> {
+        let rom = read_rom(path)?;
+        self.load_rom(&rom, addr)
+    }
+
+    pub fn load_rom(&mut self, rom: &[u8], addr: usize) -> Result<(), LoadError> {
+        self.cpu.load_rom(rom, addr)?;
+        self.rom_hash = rom_hash(rom);
+        Ok(())
+    }
+
+    /// Exécute une seule instruction à l'adresse `pc`, sauf si le programme a quitté (00FD).
+    /// En cas de faute, `pc` reste sur l'instruction fautive.
+    pub fn step(&mut self) -> Result<(), Chip8Error> {
+        if self.cpu.exited {
+            return Ok(());
+        }
+        self.cpu.check_fetch()?;
+        let opcode = self.cpu.get_opcode();
+        let instruction = match &mut self.cache {
+            Some(cache) => cache.get(self.cpu.pc as usize, opcode),
+            None => Instruction::decode(opcode),
+        };
+        self.cpu
+            .execute(instruction, &mut self.screen, &mut self.keyboard)
+    }
+
+    /// Exécute une instruction en appliquant `on_error` : la faute est remontée avec
+    /// `Halt`, sinon l'instruction fautive est sautée. Une lecture d'opcode hors de la
+    /// mémoire est toujours remontée, il n'y a pas d'instruction suivante où reprendre.
+    pub fn run_instruction(&mut self) -> Result<(), Chip8Error> {
+        let Err(error) = self.step() else {
+            return Ok(());
+        };
+        if matches!(error, Chip8Error::FetchOutOfBounds { .. }) {
+            return Err(error);
+        }
+        match self.on_error {
+            ErrorPolicy::Halt => return Err(error),
+            ErrorPolicy::Log => eprintln!("{}", error),
+            ErrorPolicy::Ignore => {}
+        }
+        self.cpu.pc = self.cpu.pc.wrapping_add(2);
+        Ok(())
+    }
+
+    /// Exécute `speed` instructions puis décrémente les timers, soit une frame à 60 Hz.
+    pub fn run_frame(&mut self, speed: usize) -> Result<(), Chip8Error> {
+        self.run_frame_until(speed, &mut |_| false).map(|_| ())
+    }
+
+    /// Comme `run_frame`, mais s'arrête avant toute instruction pour laquelle `stop` est vrai.
+    /// Retourne vrai si la frame a été interrompue, les timers ne sont alors pas décrémentés.
+    pub fn run_frame_until(
+        &mut self,
+        speed: usize,
+        stop: &mut dyn FnMut(&Machine) -> bool,
+    ) -> Result<bool, Chip8Error> {
+        for _ in 0..speed {
+            if stop(self) {
+                return Ok(true);
+            }
+            self.run_instruction()?;
+        }
+        self.tick_timers();
+        Ok(false)
+    }
+
+    /// Exécute une tranche donnée par le `Scheduler`, en répartissant les instructions
+    /// entre les ticks de timer. Retourne le nombre de ticks, donc de frames à afficher.
+    pub fn run_slice(&mut self, slice: Slice) -> Result<u32, Chip8Error> {
+        self.run_slice_until(slice, &mut |_| false)
+            .map(|(ticks, _)| ticks)
+    }
+
+    /// Comme `run_slice`, mais s'arrête avant toute instruction pour laquelle `stop` est vrai.
+    /// Retourne les ticks effectués et si la tranche a été interrompue.
+    pub fn run_slice_until(
+        &mut self,
+        slice: Slice,
+        stop: &mut dyn FnMut(&Machine) -> bool,
+    ) -> Result<(u32, bool), Chip8Error> {
+        let mut done = 0;
+        for tick in 0..slice.timer_ticks {
+            let target = slice.instructions * (tick + 1) / slice.timer_ticks;
+            for _ in done..target {
+                if stop(self) {
+                    return Ok((tick, true));
+                }
+                self.run_instruction()?;
+            }
+            done = target;
+            self.tick_timers();
+        }
+        for _ in done..slice.instructions {
+            if stop(self) {
+                return Ok((slice.timer_ticks, true));
+            }
+            self.run_instruction()?;
+        }
+        Ok((slice.timer_ticks, false))
+    }
+
+    pub fn is_stopped(&self, condition: StopCondition) -> bool {
+        match condition {
+            StopCondition::Halt => {
+                if self.cpu.exited {
+                    return true;
+                }
+                let opcode = self.cpu.check_fetch().map(|_| self.cpu.get_opcode());
+                opcode.is_ok_and(|op| op & 0xF000 == 0x1000 && op & 0x0FFF == self.cpu.pc)
+            }
+            StopCondition::KeyWait => self.keyboard.awaiting_key.is_some(),
+            StopCondition::Pc(addr) => self.cpu.pc == addr,
+        }
+    }
+
+    /// Exécute au plus `max_frames` frames, en s'arrêtant dès que `until` est vérifiée.
+    /// Retourne le nombre de frames menées à terme.
+    pub fn run_until(
+        &mut self,
+        speed: usize,
+        max_frames: u32,
+        until: Option<StopCondition>,
+    ) -> Result<u32, Chip8Error> {
+        for frame in 0..max_frames {
+            for _ in 0..speed {
+                if until.is_some_and(|c| self.is_stopped(c)) {
+                    return Ok(frame);
+                }
+                self.run_instruction()?;
+            }
+            self.tick_timers();
+        }
+        Ok(max_frames)
+    }
+}
+
+impl FromStr for StopCondition {
+    type Err = String;
+
+    /// Accepte `halt`, `keywait` ou `pc=ADDR` (adresse en hexadécimal, `0x` optionnel).
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "halt" => Ok(StopCondition::Halt),
+            "keywait" => Ok(StopCondition::KeyWait),
+            other => {
+                let addr = other
+                    .strip_prefix("pc=")
+                    .ok_or_else(|| format!("unknown stop condition '{}'", s))?;
+                let addr = addr.trim_start_matches("0x");
+                u16::from_str_radix(addr, 16)
+                    .map(StopCondition::Pc)
+                    .map_err(|e| format!("invalid address '{}': {}", addr, e))
+            }
+        }
+    }
+}
+
+impl FromStr for ErrorPolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "halt" => Ok(ErrorPolicy::Halt),
+            "log" => Ok(ErrorPolicy::Log),
+            "ignore" => Ok(ErrorPolicy::Ignore),
+            _ => Err(format!(
+                "unknown error policy '{}' (expected halt, log or ignore)",
+                s
+            )),
+        }
+    }
+}
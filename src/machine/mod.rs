@@ -0,0 +1,3 @@
+#[allow(clippy::module_inception)]
+pub mod machine;
+pub mod schema;
@@ -0,0 +1,37 @@
+use crate::audio::schema::AudioOutput;
+use crate::cpu::schema::{DecodeCache, Keyboard, CPU};
+use crate::display::schema::FrameBuffer;
+
+/// Machine CHIP-8 complète : CPU (mémoire, registres, timers), écran, clavier et sortie audio.
+/// Aucune dépendance à SDL, elle peut être pilotée par n'importe quel frontend.
+pub struct Machine {
+    pub cpu: CPU,
+    pub screen: FrameBuffer,
+    pub keyboard: Keyboard,
+    pub cache: Option<DecodeCache>, // instructions prédécodées, désactivé par défaut
+    pub audio: Box<dyn AudioOutput>,
+    pub on_error: ErrorPolicy,
+    pub rom_hash: u64, // empreinte de la ROM chargée, vérifiée par les sauvegardes d'état
+}
+
+/// Condition d'arrêt d'une exécution sans affichage.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StopCondition {
+    /// Le programme boucle sur lui-même (`1NNN` avec `NNN == pc`) ou a quitté (`00FD`).
+    Halt,
+    /// Le programme attend une touche (`FX0A`).
+    KeyWait,
+    /// Le compteur ordinal atteint cette adresse.
+    Pc(u16),
+}
+
+/// Réaction des boucles d'exécution à une faute (`Chip8Error`).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ErrorPolicy {
+    /// Arrête l'exécution et remonte la faute au frontend.
+    Halt,
+    /// Affiche la faute sur stderr et saute l'instruction fautive.
+    Log,
+    /// Saute l'instruction fautive sans rien dire.
+    Ignore,
+}
@@ -1,40 +1,333 @@
-use std::{
-    env::args,
-    time::{Duration, Instant},
-};
+use std::{path::PathBuf, time::Instant};
 
+use chip_8::audio::schema::{DEFAULT_FREQUENCY, DEFAULT_VOLUME};
+use chip_8::cpu::schema::{Profile, RngKind, CPU_SPEED};
+use chip_8::debugger::schema::Watchpoint;
+use chip_8::display::{
+    palette::parse_palette,
+    schema::{FrameBuffer, Palette, Scaling, DEFAULT_WINDOW_SCALE},
+};
+use chip_8::machine::schema::{ErrorPolicy, Machine, StopCondition};
+use chip_8::movie::schema::{Movie, Session};
+use chip_8::rewind::schema::{DEFAULT_REWIND_BUDGET, DEFAULT_REWIND_INTERVAL};
+use chip_8::rom::keymap::{find_keymap, read_keymap};
+use chip_8::video::schema::DEFAULT_VIDEO_SCALE;
 use clap::Parser;
-use cpu::schema::{Jump, Keyboard, CPU, CPU_SPEED};
-use display::schema::{ContextPixels, HEIGHT, WIDHT};
-use sdl2::{event::Event, keyboard::Keycode};
+use config::schema::FileConfig;
+use frontend::headless::run_headless;
+use tools::{asm::run_asm, disasm::run_disasm, schema::Tool};
 
-mod cpu;
-mod display;
+mod config;
+mod frontend;
+mod tools;
 
 #[derive(Parser, Debug)]
-#[command(author, version, about = "Émulateur Chip-8 en Rust")]
+#[command(
+    author,
+    version,
+    about = "Émulateur Chip-8 en Rust",
+    args_conflicts_with_subcommands = true,
+    subcommand_negates_reqs = true
+)]
 pub struct Config {
-    pub rom_path: String,
+    #[command(subcommand)]
+    pub tool: Option<Tool>,
+    /// Chemin de la ROM (.ch8, .gz ou .zip), ou `-` pour l'entrée standard
+    #[arg(required = true)]
+    pub rom_path: Option<String>,
     #[arg(short, long, default_value_t = CPU_SPEED)]
     pub speed: usize,
+    /// Vitesse du CPU en instructions par seconde (par défaut `speed` x 60)
+    #[arg(long)]
+    pub ips: Option<u32>,
     #[arg(short, long, default_value_t = false)]
     pub debug: bool,
     #[arg(short, long)]
     pub bench: Option<u32>,
-    #[arg(short, long, default_value_t = false)]
+    #[arg(long, default_value_t = false)]
     pub headless: bool,
+    /// Nombre maximum de frames exécutées en mode --headless
+    #[arg(long, default_value_t = 600)]
+    pub frames: u32,
+    /// Arrête le mode --headless plus tôt : `halt`, `keywait` ou `pc=ADDR`
+    #[arg(long)]
+    pub until: Option<StopCondition>,
+    /// Fichier où écrire l'écran final en mode --headless (.txt, .pbm ou .png)
+    #[arg(long)]
+    pub dump: Option<String>,
+    /// Couleurs de l'écran : mono, amber, green, lcd, octo, ou 2 ou 4 couleurs #RRGGBB
+    #[arg(long, value_parser = parse_palette)]
+    pub palette: Option<Palette>,
+    /// Taille d'un pixel 64x32 dans la fenêtre au démarrage (la fenêtre reste redimensionnable)
+    #[arg(long, default_value_t = DEFAULT_WINDOW_SCALE, value_parser = clap::value_parser!(u32).range(1..=64))]
+    pub scale: u32,
+    /// Mise à l'échelle dans la fenêtre : integer (pixels nets) ou aspect (toute la place)
+    #[arg(long, default_value = "integer")]
+    pub scaling: Scaling,
+    /// Démarre en plein écran (Alt+Entrée bascule)
+    #[arg(long)]
+    pub fullscreen: bool,
+    /// Agrandissement des captures PNG/PBM (F9 dans la fenêtre, --dump en headless)
+    #[arg(long, default_value_t = 1, value_parser = clap::value_parser!(u32).range(1..=64))]
+    pub screenshot_scale: u32,
+    /// Enregistre la fenêtre dans un GIF animé à 60 Hz (F10 arrête ou relance)
+    #[arg(long)]
+    pub record_video: Option<PathBuf>,
+    /// Écrit chaque frame en RGB24 brut, pour un encodeur externe
+    #[arg(long)]
+    pub record_raw: Option<PathBuf>,
+    /// Agrandissement de la grille 128x64 dans les vidéos
+    #[arg(long, default_value_t = DEFAULT_VIDEO_SCALE, value_parser = clap::value_parser!(u32).range(1..=64))]
+    pub video_scale: u32,
+    /// Quirks de l'interpréteur émulé : vip, chip48, schip, xochip ou modern
+    #[arg(long, default_value = "modern")]
+    pub profile: Profile,
+    /// Fréquence du bip en Hz
+    #[arg(long, default_value_t = DEFAULT_FREQUENCY)]
+    pub frequency: f32,
+    /// Volume du bip, entre 0.0 et 1.0
+    #[arg(long, default_value_t = DEFAULT_VOLUME)]
+    pub volume: f32,
+    /// Coupe le son
+    #[arg(long, default_value_t = false)]
+    pub mute: bool,
+    /// Prédécode la ROM au chargement
+    #[arg(long, default_value_t = false)]
+    pub predecode: bool,
+    /// Réaction à une faute du programme : halt, log ou ignore
+    #[arg(long, default_value = "halt")]
+    pub on_error: ErrorPolicy,
+    /// Adresse de chargement de la ROM en hexadécimal (600 pour l'ETI-660)
+    #[arg(long, default_value = "200", value_parser = parse_addr)]
+    pub load_addr: usize,
+    /// Remappage du pavé (.c8k), par défaut le .c8k à côté de la ROM s'il existe
+    #[arg(long)]
+    pub keymap: Option<String>,
+    /// Démarre en pause dans le débogueur interactif (F12 le rappelle dans la fenêtre)
+    #[arg(long, default_value_t = false)]
+    pub debugger: bool,
+    /// Point de surveillance, répétable : `ADDR[-END] [r|w|rw] [log]`, `REG [log]` ou `REG OP VALUE [log]`
+    #[arg(long)]
+    pub watch: Vec<Watchpoint>,
+    /// Reprend la partie depuis une sauvegarde d'état (.c8s) faite avec la même ROM
+    #[arg(long)]
+    pub load_state: Option<PathBuf>,
+    /// Mémoire réservée au retour en arrière (Retour arrière maintenu), en Mio, 0 le désactive
+    #[arg(long, default_value_t = DEFAULT_REWIND_BUDGET >> 20)]
+    pub rewind_budget: usize,
+    /// Frames entre deux instantanés du retour en arrière
+    #[arg(long, default_value_t = DEFAULT_REWIND_INTERVAL)]
+    pub rewind_interval: u32,
+    /// Générateur de CXNN : os (par défaut), xorshift, fonttable ou seq:XX,YY,... (suite imposée)
+    #[arg(long)]
+    pub rng: Option<RngKind>,
+    /// Graine du générateur de CXNN, qui devient xorshift si --rng n'est pas donné
+    #[arg(long)]
+    pub seed: Option<u64>,
+    /// Enregistre la session (graine de CXNN et touches de chaque frame) dans un film
+    #[arg(long, conflicts_with_all = ["replay", "debugger", "watch"])]
+    pub record: Option<PathBuf>,
+    /// Rejoue un film enregistré avec --record, à l'identique
+    #[arg(long, conflicts_with_all = ["load_state", "debugger", "watch", "rng", "seed"])]
+    pub replay: Option<PathBuf>,
+}
+
+fn parse_addr(s: &str) -> Result<usize, String> {
+    usize::from_str_radix(s.trim_start_matches("0x"), 16).map_err(|e| e.to_string())
 }
 
 fn main() -> Result<(), String> {
+    let config = Config::parse();
+    if let Some(tool) = &config.tool {
+        return run_tool(tool);
+    }
+    // clap exige la ROM quand aucun outil n'est demandé
+    let rom_path = config.rom_path.as_deref().unwrap_or_default();
+
+    let settings = FileConfig::load(rom_path)?;
+
+    let mut machine = Machine::new(config.debug, config.profile.quirks());
+    machine.cpu.set_mem_size(config.profile.mem_size());
+
+    match machine.load_game(rom_path, config.load_addr) {
+        Ok(()) => println!("Game was loaded succesfully !"),
+        Err(e) => {
+            return Err(format!(
+                "An error has occured during loading game {} : {}",
+                rom_path, e
+            ))
+        }
+    }
+
+    let keymap = config
+        .keymap
+        .clone()
+        .map(PathBuf::from)
+        .or_else(|| find_keymap(rom_path));
+    // un .c8k illisible ne doit pas empêcher de jouer, le pavé reste tel quel
+    if let Some(path) = keymap {
+        match read_keymap(&path) {
+            Ok(keymap) => {
+                machine.keyboard.set_keymap(keymap);
+                println!("Keymap loaded from {}", path.display());
+            }
+            Err(e) => eprintln!(
+                "Cannot load keymap {}: {}, using the default mapping",
+                path.display(),
+                e
+            ),
+        }
+    }
+
+    let rng = match (&config.rng, config.seed) {
+        (Some(rng), _) => rng.clone(),
+        (None, Some(_)) => RngKind::XorShift,
+        (None, None) => RngKind::Os,
+    };
+    let seed = config.seed.unwrap_or_else(rand::random);
+    // avant --load-state, qui restaure le générateur sauvegardé avec l'état
+    machine.cpu.rng = rng.source(seed);
+
+    if let Some(path) = &config.load_state {
+        machine
+            .load_state_file(path)
+            .map_err(|e| format!("cannot load state {}: {}", path.display(), e))?;
+        println!("State loaded from {}", path.display());
+    }
+
+    machine.on_error = config.on_error;
+    if config.predecode {
+        machine.enable_decode_cache();
+    }
+
+    let session = match (&config.record, &config.replay) {
+        (Some(_), _) => Some(Session::Record(Movie::start(
+            &mut machine,
+            config.speed as u32,
+            &rng,
+            seed,
+        ))),
+        (_, Some(path)) => {
+            let movie = Movie::load(path)
+                .map_err(|e| format!("cannot load movie {}: {}", path.display(), e))?;
+            movie
+                .rewind(&mut machine)
+                .map_err(|e| format!("cannot replay {}: {}", path.display(), e))?;
+            println!(
+                "Replaying {} frame(s) from {}",
+                movie.frames.len(),
+                path.display()
+            );
+            Some(Session::Replay { movie, frame: 0 })
+        }
+        _ => None,
+    };
+
+    let palette = match config.palette {
+        Some(palette) => palette,
+        None => settings.display.palette()?,
+    };
+
+    if config.headless {
+        if config.record_video.is_some() || config.record_raw.is_some() {
+            return Err(
+                "video recording captures the window, it is not available with --headless".into(),
+            );
+        }
+        if let Some(bench) = config.bench {
+            return run_bench(&mut machine, &config, bench, |_| {});
+        }
+        return run_headless(&mut machine, &config, session, &palette);
+    }
+
+    run_window(machine, &config, &settings, session, &palette)
+}
+
+fn run_tool(tool: &Tool) -> Result<(), String> {
+    match tool {
+        Tool::Asm {
+            source,
+            output,
+            defines,
+        } => run_asm(source, output, defines),
+        Tool::Disasm {
+            rom,
+            output,
+            profile,
+            load_addr,
+        } => run_disasm(rom, output.as_deref(), *profile, *load_addr),
+    }
+}
+
+/// Exécute `frames` frames le plus vite possible et affiche le nombre d'instructions par seconde.
+fn run_bench(
+    machine: &mut Machine,
+    config: &Config,
+    frames: u32,
+    mut present: impl FnMut(&FrameBuffer),
+) -> Result<(), String> {
+    println!("Start Benchmark ...");
+    let start = Instant::now();
+    for _ in 0..frames {
+        machine
+            .run_frame(config.speed)
+            .map_err(|e| format!("emulation stopped: {}", e))?;
+        present(&machine.screen);
+    }
+    let elapsed = start.elapsed();
+    let ips = (frames as f64 * config.speed as f64) / elapsed.as_secs_f64();
+    println!(
+        "Executed {} instructions in {:?}",
+        frames * config.speed as u32,
+        elapsed
+    );
+    println!("Instructions per second: {:.2}", ips);
+    Ok(())
+}
+
+#[cfg(feature = "sdl")]
+fn run_window(
+    mut machine: Machine,
+    config: &Config,
+    settings: &FileConfig,
+    mut session: Option<Session>,
+    palette: &Palette,
+) -> Result<(), String> {
+    use chip_8::scheduler::schema::{Scheduler, TIMER_HZ};
+
+    use chip_8::debugger::schema::{Action, Debugger};
+    use chip_8::display::schema::{H, W};
+
+    use chip_8::input::schema::Input;
+    use chip_8::rewind::schema::Rewind;
+    use frontend::debugger::{attach, print_events, prompt, report_break};
+    use frontend::headless::save_movie;
+    use frontend::schema::{
+        ContextPixels, Gamepads, HostLayout, SdlAudio, StateHotkey, VideoCapture,
+    };
+    use frontend::sdl::{
+        fullscreen_hotkey, quick_state, screenshot_hotkey, state_hotkey, take_screenshot,
+        video_hotkey,
+    };
+    use sdl2::{
+        event::{Event, WindowEvent},
+        keyboard::Keycode,
+    };
+
+    let rom_path = config.rom_path.as_deref().unwrap_or_default();
+    let layout = HostLayout::from_config(&settings.keyboard)?;
+    let mut input = Input::new(settings.gamepad.mappings()?, settings.gamepad.threshold()?);
     let sdl_context = sdl2::init()?;
     let video_subsystem = sdl_context.video()?;
-    let config = Config::parse();
+    let mut gamepads = Gamepads::new(sdl_context.game_controller()?);
 
-    let window = video_subsystem
-        .window("Chip8", WIDHT, HEIGHT)
-        .position_centered()
-        .build()
-        .map_err(|e| e.to_string())?;
+    // la taille de la fenêtre ne dépend que de --scale : passer en 128x64 rapetisse les pixels
+    let mut builder = video_subsystem.window("Chip8", W * config.scale, H * config.scale);
+    builder.position_centered().resizable();
+    if config.fullscreen {
+        builder.fullscreen_desktop();
+    }
+    let window = builder.build().map_err(|e| e.to_string())?;
 
     let canvas = window
         .into_canvas()
@@ -46,82 +339,188 @@ fn main() -> Result<(), String> {
 
     let mut event_pump = sdl_context.event_pump()?;
 
-    let mut ctx = ContextPixels::init(canvas, &texture_creator);
-    let mut cpu = CPU::new(config.debug);
-
-    match cpu.load_game(&config.rom_path) {
-        Ok(()) => println!("Game was loaded succesfully !"),
-        Err(e) => {
-            println!("An error has occured during loading game : {}", e);
-            return Ok(());
+    // sans périphérique audio la machine garde sa sortie muette
+    if !config.mute {
+        let opened = sdl_context
+            .audio()
+            .and_then(|audio| SdlAudio::open(&audio, config.frequency, config.volume));
+        match opened {
+            Ok(audio) => machine.set_audio(Box::new(audio)),
+            Err(e) => eprintln!("No audio device ({}), running without sound", e),
         }
     }
-    cpu.init_memory(); //mapper la police
 
-    let j = Jump::new();
+    let mut ctx = ContextPixels::init(canvas, &texture_creator, palette, config.scaling);
 
     if let Some(bench) = config.bench {
-        println!("Start Benchmark ...");
-        let start = Instant::now();
-        for _ in 0..bench {
-            for _ in 0..config.speed {
-                let opcode = cpu.get_opcode();
-                cpu.interpret(opcode, &j, &mut ctx);
-            }
+        return run_bench(&mut machine, config, bench, |fb| ctx.update_screen(fb));
+    }
 
-            if !config.headless {
-                ctx.update_screen();
-            }
-            cpu.countdown();
-        }
-        let elapsed = start.elapsed();
-        let ips = (bench as f64 * config.speed as f64) / elapsed.as_secs_f64();
-        println!(
-            "Executed {} instructions in {:?}",
-            bench * config.speed as u32,
-            elapsed
-        );
-        println!("Instructions per second: {:.2}", ips);
-        return Ok(());
+    let ips = config.ips.unwrap_or(config.speed as u32 * TIMER_HZ);
+    let mut scheduler = Scheduler::new(ips, Instant::now());
+    let mut debugger = attach(config, &machine);
+    if let Some(dbg) = debugger.as_mut().filter(|d| d.paused) {
+        ctx.update_screen(&machine.screen);
+        report_break(dbg, &machine, "Paused at start, type 'help' for commands");
     }
 
-    'running: loop {
+    // revenir en arrière casserait la suite des frames d'un film
+    let mut rewind = (config.rewind_budget > 0 && session.is_none())
+        .then(|| Rewind::new(config.rewind_budget << 20, config.rewind_interval));
+    let mut rewinding = false;
+    let mut video = VideoCapture::from_config(config, palette)?;
+
+    let outcome = 'running: loop {
         for event in event_pump.poll_iter() {
             match event {
                 Event::Quit { .. }
                 | Event::KeyDown {
                     keycode: Some(Keycode::Escape),
                     ..
-                } => break 'running,
+                } => break 'running Ok(()),
                 Event::KeyDown {
-                    keycode: Some(keycode),
+                    keycode: Some(Keycode::F12),
+                    ..
+                } if session.is_some() => eprintln!("The debugger is not available during a movie"),
+                Event::KeyDown {
+                    keycode: Some(Keycode::F12),
                     ..
                 } => {
-                    if let Some(chip8_key) = Keyboard::map_sdl_key_to_chip8(keycode) {
-                        ctx.keyboard.set_key(chip8_key, true);
+                    let dbg = debugger.get_or_insert_with(Debugger::default);
+                    dbg.paused = true;
+                    report_break(dbg, &machine, "Paused, type 'help' for commands");
+                }
+                Event::KeyDown {
+                    keycode: Some(Keycode::Backspace),
+                    ..
+                } => rewinding = rewind.is_some(),
+                Event::KeyUp {
+                    keycode: Some(Keycode::Backspace),
+                    ..
+                } => rewinding = false,
+                Event::ControllerDeviceAdded { which, .. } => {
+                    gamepads.connect(which);
+                }
+                Event::ControllerDeviceRemoved { which, .. } => {
+                    if let Some(player) = gamepads.disconnect(which) {
+                        input.release_player(player, &mut machine.keyboard);
                     }
                 }
-                sdl2::event::Event::KeyUp {
-                    keycode: Some(keycode),
+                Event::Window {
+                    win_event: WindowEvent::SizeChanged(..),
                     ..
-                } => {
-                    if let Some(chip8_key) = Keyboard::map_sdl_key_to_chip8(keycode) {
-                        ctx.keyboard.set_key(chip8_key, false);
+                } => ctx.update_screen(&machine.screen),
+                event => {
+                    if fullscreen_hotkey(&event) {
+                        ctx.toggle_fullscreen();
+                    } else if video_hotkey(&event) {
+                        video.toggle(rom_path);
+                    } else if let Some(ext) = screenshot_hotkey(&event) {
+                        let scale = config.screenshot_scale;
+                        take_screenshot(&machine.screen, rom_path, ext, palette, scale);
+                    } else if let Some(hotkey) = state_hotkey(&event) {
+                        if session.is_some() && matches!(hotkey, StateHotkey::Load(_)) {
+                            eprintln!("Loading a state is disabled during a movie");
+                            continue;
+                        }
+                        quick_state(&mut machine, rom_path, hotkey);
+                        ctx.update_screen(&machine.screen);
+                    } else if let Some(event) = gamepads.translate(&event, &layout) {
+                        input.handle(event, &mut machine.keyboard);
                     }
                 }
-                _ => {}
             }
         }
 
-        for _ in 0..config.speed {
-            let opcode = cpu.get_opcode();
-            cpu.interpret(opcode, &j, &mut ctx);
+        // la fenêtre reste figée pendant que l'invite attend une commande
+        if let Some(dbg) = debugger.as_mut().filter(|d| d.paused) {
+            match prompt(dbg, &mut machine) {
+                Action::Quit => break 'running Ok(()),
+                Action::Resume => scheduler = Scheduler::new(ips, Instant::now()),
+                Action::Paused => {}
+            }
+            ctx.update_screen(&machine.screen);
+            continue;
+        }
+
+        let slice = scheduler.advance(Instant::now());
+        if let Some(history) = rewind.as_mut().filter(|_| rewinding) {
+            // un instantané par tick : le jeu recule à sa vitesse normale avec un intervalle de 1
+            for _ in 0..slice.timer_ticks {
+                history.step_back(&mut machine);
+            }
+            ctx.update_screen(&machine.screen);
+            video.push(&machine.screen, slice.timer_ticks);
+            std::thread::sleep(scheduler.until_next_tick());
+            continue;
+        }
+        let result = match (debugger.as_mut(), session.as_mut()) {
+            (Some(dbg), _) => machine.run_slice_until(slice, &mut |m| dbg.should_break(m)),
+            // pendant un film, chaque tick exécute exactement `speed` instructions
+            (None, Some(movie)) => (0..slice.timer_ticks)
+                .try_fold(0, |ticks, _| {
+                    Ok(ticks + movie.run_frame(&mut machine)? as u32)
+                })
+                .map(|ticks| (ticks, false)),
+            (None, None) => machine.run_slice(slice).map(|ticks| (ticks, false)),
+        };
+        match (result, debugger.as_mut()) {
+            (Ok((_, true)), Some(dbg)) => {
+                ctx.update_screen(&machine.screen);
+                report_break(dbg, &machine, "Break");
+            }
+            (Ok((ticks, _)), dbg) => {
+                if let Some(dbg) = dbg {
+                    print_events(dbg);
+                }
+                if let Some(history) = rewind.as_mut() {
+                    history.record(&machine, ticks);
+                }
+                if ticks > 0 {
+                    ctx.update_screen(&machine.screen);
+                    video.push(&machine.screen, ticks);
+                }
+            }
+            (Err(e), Some(dbg)) => {
+                // sous le débogueur, une faute met en pause au lieu d'arrêter
+                ctx.update_screen(&machine.screen);
+                dbg.paused = true;
+                report_break(dbg, &machine, &e.to_string());
+            }
+            (Err(e), None) => {
+                // on garde le dernier écran affiché, le message explique l'arrêt
+                ctx.update_screen(&machine.screen);
+                break 'running Err(format!("emulation stopped: {}", e));
+            }
+        }
+
+        if let Some(Session::Replay { movie, frame }) = &session {
+            if *frame == movie.frames.len() {
+                println!("Replay finished, the keyboard is live again");
+                session = None;
+            }
         }
 
-        ctx.update_screen();
-        cpu.countdown();
+        if machine.cpu.exited {
+            break 'running Ok(());
+        }
+
+        std::thread::sleep(scheduler.until_next_tick());
+    };
 
-        std::thread::sleep(Duration::from_millis(16));
+    if let (Some(Session::Record(movie)), Some(path)) = (&session, &config.record) {
+        save_movie(movie, path)?;
     }
-    Ok(())
+    outcome
+}
+
+#[cfg(not(feature = "sdl"))]
+fn run_window(
+    _machine: Machine,
+    _config: &Config,
+    _settings: &FileConfig,
+    _session: Option<Session>,
+    _palette: &Palette,
+) -> Result<(), String> {
+    Err("this build has no SDL support, use --headless".to_string())
 }
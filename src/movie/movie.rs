@@ -0,0 +1,155 @@
+use std::{fmt, fs, io, path::Path};
+
+use crate::cpu::schema::{Chip8Error, Keyboard, RngKind};
+use crate::machine::schema::Machine;
+use crate::state::schema::{Reader, StateError};
+
+use super::schema::{Movie, MovieError, Session, MOVIE_MAGIC, MOVIE_VERSION};
+
+impl Movie {
+    /// Commence un film à partir de l'état courant ; le générateur de CXNN repart de
+    /// `seed`, l'entropie du système étant remplacée par un générateur rejouable.
+    pub fn start(machine: &mut Machine, speed: u32, rng: &RngKind, seed: u64) -> Self {
+        let rng = rng.replayable();
+        machine.cpu.rng = rng.source(seed);
+        Movie {
+            rng,
+            seed,
+            speed,
+            start: machine.save_state(),
+            frames: Vec::new(),
+        }
+    }
+
+    /// Remet la machine dans l'état du début du film.
+    pub fn rewind(&self, machine: &mut Machine) -> Result<(), MovieError> {
+        machine.load_state(&self.start)?;
+        machine.cpu.rng = self.rng.source(self.seed);
+        machine.keyboard.keys = [false; 16];
+        Ok(())
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(self.start.len() + 2 * self.frames.len() + 32);
+        out.extend_from_slice(&MOVIE_MAGIC);
+        out.extend_from_slice(&MOVIE_VERSION.to_le_bytes());
+        let rng = self.rng.to_string();
+        out.extend_from_slice(&(rng.len() as u16).to_le_bytes());
+        out.extend_from_slice(rng.as_bytes());
+        out.extend_from_slice(&self.seed.to_le_bytes());
+        out.extend_from_slice(&self.speed.to_le_bytes());
+        out.extend_from_slice(&(self.start.len() as u32).to_le_bytes());
+        out.extend_from_slice(&self.start);
+        out.extend_from_slice(&(self.frames.len() as u32).to_le_bytes());
+        for keys in &self.frames {
+            out.extend_from_slice(&keys.to_le_bytes());
+        }
+        out
+    }
+
+    pub fn from_bytes(data: &[u8]) -> Result<Self, MovieError> {
+        let mut r = Reader { data, pos: 0 };
+        if r.bytes(MOVIE_MAGIC.len())? != MOVIE_MAGIC {
+            return Err(MovieError::BadMagic);
+        }
+        let version = r.u16()?;
+        if version != MOVIE_VERSION {
+            return Err(MovieError::UnsupportedVersion(version));
+        }
+        let len = r.u16()? as usize;
+        let rng = String::from_utf8_lossy(r.bytes(len)?);
+        let rng = rng.parse().map_err(MovieError::BadRandomSource)?;
+        let seed = r.u64()?;
+        let speed = r.u32()?;
+        let len = r.u32()? as usize;
+        let start = r.bytes(len)?.to_vec();
+        let count = r.u32()? as usize;
+        let frames = (0..count).map(|_| r.u16()).collect::<Result<_, _>>()?;
+        Ok(Movie {
+            rng,
+            seed,
+            speed,
+            start,
+            frames,
+        })
+    }
+
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        fs::write(path, self.to_bytes())
+    }
+
+    pub fn load(path: &Path) -> Result<Self, MovieError> {
+        Self::from_bytes(&fs::read(path)?)
+    }
+}
+
+impl Session {
+    pub fn movie(&self) -> &Movie {
+        match self {
+            Session::Record(movie) | Session::Replay { movie, .. } => movie,
+        }
+    }
+
+    /// Exécute une frame de `speed` instructions : les touches sont notées, ou imposées
+    /// par le film. Retourne faux sans rien exécuter quand la relecture est finie.
+    pub fn run_frame(&mut self, machine: &mut Machine) -> Result<bool, Chip8Error> {
+        let speed = self.movie().speed as usize;
+        match self {
+            Session::Record(movie) => movie.frames.push(key_bits(&machine.keyboard)),
+            Session::Replay { movie, frame } => {
+                let Some(&bits) = movie.frames.get(*frame) else {
+                    return Ok(false);
+                };
+                *frame += 1;
+                for (key, pressed) in machine.keyboard.keys.iter_mut().enumerate() {
+                    *pressed = bits & (1 << key) != 0;
+                }
+            }
+        }
+        machine.run_frame(speed)?;
+        Ok(true)
+    }
+}
+
+fn key_bits(keyboard: &Keyboard) -> u16 {
+    keyboard
+        .keys
+        .iter()
+        .enumerate()
+        .fold(0, |bits, (key, &pressed)| bits | (pressed as u16) << key)
+}
+
+impl From<io::Error> for MovieError {
+    fn from(e: io::Error) -> Self {
+        MovieError::Io(e)
+    }
+}
+
+impl From<StateError> for MovieError {
+    fn from(e: StateError) -> Self {
+        match e {
+            // le lecteur partagé signale la fin du fichier du film lui-même
+            StateError::Truncated => MovieError::Truncated,
+            e => MovieError::State(e),
+        }
+    }
+}
+
+impl fmt::Display for MovieError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MovieError::Io(e) => write!(f, "{}", e),
+            MovieError::BadMagic => write!(f, "not a movie file"),
+            MovieError::UnsupportedVersion(version) => write!(
+                f,
+                "movie version {} is not supported (expected {})",
+                version, MOVIE_VERSION
+            ),
+            MovieError::Truncated => write!(f, "movie file is truncated"),
+            MovieError::BadRandomSource(reason) => write!(f, "{}", reason),
+            MovieError::State(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for MovieError {}
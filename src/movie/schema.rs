@@ -0,0 +1,41 @@
+use std::io;
+
+use crate::cpu::schema::RngKind;
+use crate::state::schema::StateError;
+
+pub const MOVIE_MAGIC: [u8; 4] = *b"C8MV";
+pub const MOVIE_VERSION: u16 = 2;
+
+/// Session enregistrée : l'état de départ (sauvegarde d'état, qui porte l'empreinte
+/// de la ROM), le générateur de CXNN et sa graine, les instructions par frame, puis les touches CHIP-8
+/// enfoncées au début de chaque frame (bit `i` pour la touche `i`).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Movie {
+    pub rng: RngKind,
+    pub seed: u64,
+    pub speed: u32,
+    pub start: Vec<u8>,
+    pub frames: Vec<u16>,
+}
+
+/// Enregistrement ou relecture en cours, avancé frame par frame.
+pub enum Session {
+    Record(Movie),
+    Replay { movie: Movie, frame: usize },
+}
+
+/// Raison pour laquelle un film n'a pas pu être relu.
+#[derive(Debug)]
+pub enum MovieError {
+    Io(io::Error),
+    /// Le fichier ne commence pas par `MOVIE_MAGIC`.
+    BadMagic,
+    /// Film écrit par une autre version du format.
+    UnsupportedVersion(u16),
+    /// Le fichier s'arrête avant la dernière frame annoncée.
+    Truncated,
+    /// Générateur de CXNN inconnu de cette version.
+    BadRandomSource(String),
+    /// L'état de départ est refusé, par exemple parce que la ROM diffère.
+    State(StateError),
+}
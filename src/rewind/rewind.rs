@@ -0,0 +1,145 @@
+use std::collections::VecDeque;
+
+use crate::machine::schema::Machine;
+
+use super::schema::Rewind;
+
+impl Rewind {
+    pub fn new(budget: usize, interval: u32) -> Self {
+        Rewind {
+            budget,
+            interval: interval.max(1),
+            frames: 0,
+            latest: Vec::new(),
+            deltas: VecDeque::new(),
+            used: 0,
+        }
+    }
+
+    /// À appeler après `ticks` frames émulées ; prend un instantané toutes les
+    /// `interval` frames et oublie les plus anciens au-delà du budget.
+    pub fn record(&mut self, machine: &Machine, ticks: u32) {
+        self.frames += ticks;
+        if self.frames < self.interval {
+            return;
+        }
+        self.frames = 0;
+
+        let state = machine.save_state();
+        if state.len() != self.latest.len() {
+            // premier instantané, ou la taille de la mémoire a changé
+            self.deltas.clear();
+            self.used = 0;
+        } else {
+            let delta = encode_delta(&self.latest, &state);
+            self.used += delta.len();
+            self.deltas.push_back(delta);
+        }
+        self.latest = state;
+
+        while self.used + self.latest.len() > self.budget {
+            let Some(oldest) = self.deltas.pop_front() else {
+                break;
+            };
+            self.used -= oldest.len();
+        }
+    }
+
+    /// Recule d'un instantané et le recharge dans la machine. Retourne faux quand
+    /// l'historique est épuisé, la machine reste alors sur l'état le plus ancien.
+    /// Un instantané refusé par la machine (autre ROM chargée depuis) vide l'historique
+    /// et laisse la machine intacte.
+    pub fn step_back(&mut self, machine: &mut Machine) -> bool {
+        let Some(delta) = self.deltas.pop_back() else {
+            return false;
+        };
+        self.used -= delta.len();
+        apply_delta(&mut self.latest, &delta);
+        self.frames = 0;
+        if machine.load_state(&self.latest).is_err() {
+            self.clear();
+            return false;
+        }
+        true
+    }
+
+    /// Oublie tout l'historique.
+    pub fn clear(&mut self) {
+        self.frames = 0;
+        self.latest.clear();
+        self.deltas.clear();
+        self.used = 0;
+    }
+
+    /// Durée couverte par l'historique, en frames.
+    pub fn len_frames(&self) -> usize {
+        self.deltas.len() * self.interval as usize
+    }
+}
+
+/// Code `old ^ new` en suites (octets identiques à sauter, longueur, octets XOR),
+/// les deux nombres en LEB128. Appliquer le delta à l'un redonne l'autre.
+fn encode_delta(old: &[u8], new: &[u8]) -> Vec<u8> {
+    let mut out = Vec::new();
+    let mut pos = 0;
+    while pos < new.len() {
+        let start = pos;
+        while pos < new.len() && old[pos] == new[pos] {
+            pos += 1;
+        }
+        if pos == new.len() {
+            break;
+        }
+        let skip = pos - start;
+        let diff = pos;
+        while pos < new.len() && old[pos] != new[pos] {
+            pos += 1;
+        }
+        push_varint(&mut out, skip);
+        push_varint(&mut out, pos - diff);
+        out.extend(
+            old[diff..pos]
+                .iter()
+                .zip(&new[diff..pos])
+                .map(|(a, b)| a ^ b),
+        );
+    }
+    out
+}
+
+fn apply_delta(state: &mut [u8], delta: &[u8]) {
+    let mut pos = 0;
+    let mut rest = delta;
+    while !rest.is_empty() {
+        let skip = read_varint(&mut rest);
+        let len = read_varint(&mut rest);
+        pos += skip;
+        for (byte, x) in state[pos..pos + len].iter_mut().zip(&rest[..len]) {
+            *byte ^= x;
+        }
+        pos += len;
+        rest = &rest[len..];
+    }
+}
+
+fn push_varint(out: &mut Vec<u8>, mut value: usize) {
+    while value >= 0x80 {
+        out.push(value as u8 | 0x80);
+        value >>= 7;
+    }
+    out.push(value as u8);
+}
+
+fn read_varint(data: &mut &[u8]) -> usize {
+    let mut value = 0;
+    let mut shift = 0;
+    while let Some((&byte, rest)) = data.split_first() {
+        *data = rest;
+        value |= ((byte & 0x7F) as usize) << shift;
+        if byte & 0x80 == 0 {
+            break;
+        }
+        shift += 7;
+    }
+    value
+}
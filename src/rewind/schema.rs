@@ -0,0 +1,16 @@
+use std::collections::VecDeque;
+
+pub const DEFAULT_REWIND_BUDGET: usize = 8 << 20; // 8 Mio, plusieurs minutes de jeu
+pub const DEFAULT_REWIND_INTERVAL: u32 = 1; // un instantané par frame
+
+/// Historique des états de la machine pour revenir en arrière.
+/// Seul le dernier instantané est gardé en entier : chaque delta du tampon redonne
+/// l'instantané précédent à partir du suivant (XOR des deux, plages nulles sautées).
+pub struct Rewind {
+    pub budget: usize,   // octets au plus pour `latest` et `deltas`
+    pub interval: u32,   // frames entre deux instantanés
+    pub frames: u32,     // frames depuis le dernier instantané
+    pub latest: Vec<u8>, // vide tant qu'aucun instantané n'a été pris
+    pub deltas: VecDeque<Vec<u8>>,
+    pub used: usize, // octets occupés par `deltas`
+}
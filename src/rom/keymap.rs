@@ -0,0 +1,67 @@
+use std::{
+    fmt, fs, io,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use crate::cpu::schema::Keymap;
+
+use super::schema::{KeymapError, KEYMAP_EXT, STDIN_PATH};
+
+/// Lit un fichier .c8k.
+pub fn read_keymap(path: &Path) -> Result<Keymap, KeymapError> {
+    fs::read_to_string(path)?.parse()
+}
+
+/// Cherche un .c8k portant le nom de la ROM dans le même dossier (`TETRIS.ch8` -> `TETRIS.c8k`).
+pub fn find_keymap(rom_path: &str) -> Option<PathBuf> {
+    if rom_path == STDIN_PATH {
+        return None;
+    }
+    let rom = Path::new(rom_path);
+    [KEYMAP_EXT.to_string(), KEYMAP_EXT.to_ascii_uppercase()]
+        .iter()
+        .map(|ext| rom.with_extension(ext))
+        .find(|path| path.is_file())
+}
+
+/// 16 chiffres hexadécimaux, le i-ème donne la touche du pavé qui déclenche la touche `i`.
+impl FromStr for Keymap {
+    type Err = KeymapError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let digits: Vec<char> = s.chars().filter(|c| !c.is_whitespace()).collect();
+        if digits.len() != 16 {
+            return Err(KeymapError::Invalid(format!(
+                "expected 16 hex digits, found {}",
+                digits.len()
+            )));
+        }
+
+        let mut keys = [0; 16];
+        for (key, digit) in keys.iter_mut().zip(digits) {
+            *key = digit
+                .to_digit(16)
+                .ok_or_else(|| KeymapError::Invalid(format!("'{}' is not a hex digit", digit)))?
+                as u8;
+        }
+        Ok(Keymap { keys })
+    }
+}
+
+impl From<io::Error> for KeymapError {
+    fn from(e: io::Error) -> Self {
+        KeymapError::Io(e)
+    }
+}
+
+impl fmt::Display for KeymapError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            KeymapError::Io(e) => write!(f, "{}", e),
+            KeymapError::Invalid(reason) => write!(f, "invalid keymap: {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for KeymapError {}
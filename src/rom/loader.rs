@@ -0,0 +1,100 @@
+use std::{
+    fmt,
+    fs::File,
+    io::{self, Cursor, Read},
+};
+
+use flate2::read::GzDecoder;
+
+use super::schema::{LoadError, STDIN_PATH};
+
+const GZIP_MAGIC: [u8; 2] = [0x1F, 0x8B];
+const ZIP_MAGIC: [u8; 4] = [b'P', b'K', 0x03, 0x04];
+
+/// Lit une ROM depuis un fichier, ou l'entrée standard pour `-`. Les archives gzip et zip
+/// (un seul fichier) sont reconnues à leur signature et décompressées.
+pub fn read_rom(path: &str) -> Result<Vec<u8>, LoadError> {
+    let mut buffer = Vec::new();
+    if path == STDIN_PATH {
+        io::stdin().read_to_end(&mut buffer)?;
+    } else {
+        File::open(path)?.read_to_end(&mut buffer)?;
+    }
+    unpack_rom(buffer)
+}
+
+/// Décompresse `data` si c'est une archive gzip ou zip, sinon la retourne telle quelle.
+pub fn unpack_rom(data: Vec<u8>) -> Result<Vec<u8>, LoadError> {
+    let rom = if data.starts_with(&GZIP_MAGIC) {
+        let mut rom = Vec::new();
+        GzDecoder::new(&data[..])
+            .read_to_end(&mut rom)
+            .map_err(|e| LoadError::Archive(format!("invalid gzip data: {}", e)))?;
+        rom
+    } else if data.starts_with(&ZIP_MAGIC) {
+        unzip_single(&data)?
+    } else {
+        data
+    };
+
+    if rom.is_empty() {
+        return Err(LoadError::Empty);
+    }
+    Ok(rom)
+}
+
+fn unzip_single(data: &[u8]) -> Result<Vec<u8>, LoadError> {
+    let mut archive = zip::ZipArchive::new(Cursor::new(data))
+        .map_err(|e| LoadError::Archive(format!("invalid zip archive: {}", e)))?;
+
+    let files: Vec<usize> = (0..archive.len())
+        .filter(|&i| archive.by_index(i).is_ok_and(|f| f.is_file()))
+        .collect();
+
+    let index = match files.as_slice() {
+        [] => return Err(LoadError::NoRomInArchive),
+        [index] => *index,
+        _ => return Err(LoadError::MultipleRomsInArchive(files.len())),
+    };
+
+    let mut file = archive
+        .by_index(index)
+        .map_err(|e| LoadError::Archive(format!("invalid zip entry: {}", e)))?;
+    let mut rom = Vec::new();
+    file.read_to_end(&mut rom)?;
+    Ok(rom)
+}
+
+impl From<io::Error> for LoadError {
+    fn from(e: io::Error) -> Self {
+        LoadError::Io(e)
+    }
+}
+
+impl fmt::Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LoadError::Io(e) => write!(f, "{}", e),
+            LoadError::Empty => write!(f, "the ROM is empty"),
+            LoadError::TooLarge { size, max } => write!(
+                f,
+                "the ROM is {} bytes but only {} bytes fit in memory from the load address",
+                size, max
+            ),
+            LoadError::BadLoadAddress { addr, mem_size } => write!(
+                f,
+                "load address {:#05X} is outside the {} bytes of memory",
+                addr, mem_size
+            ),
+            LoadError::Archive(reason) => write!(f, "{}", reason),
+            LoadError::NoRomInArchive => write!(f, "the zip archive contains no file"),
+            LoadError::MultipleRomsInArchive(count) => write!(
+                f,
+                "the zip archive contains {} files, expected a single ROM",
+                count
+            ),
+        }
+    }
+}
+
+impl std::error::Error for LoadError {}
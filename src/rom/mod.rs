@@ -0,0 +1,3 @@
+pub mod keymap;
+pub mod loader;
+pub mod schema;
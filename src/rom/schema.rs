@@ -0,0 +1,37 @@
+use std::io;
+
+pub const ETI660_ADRR: usize = 0x600; // adresse de chargement des ROMs ETI-660
+pub const STDIN_PATH: &str = "-"; // chemin spécial pour lire la ROM sur l'entrée standard
+pub const KEYMAP_EXT: &str = "c8k"; // remappage du pavé livré à côté de la ROM
+
+/// Raison pour laquelle une ROM n'a pas pu être chargée.
+#[derive(Debug)]
+pub enum LoadError {
+    Io(io::Error),
+    /// Le fichier (ou l'archive) ne contient aucun octet.
+    Empty,
+    /// La ROM dépasse la mémoire disponible à partir de l'adresse de chargement.
+    TooLarge {
+        size: usize,
+        max: usize,
+    },
+    /// L'adresse de chargement est hors de la mémoire.
+    BadLoadAddress {
+        addr: usize,
+        mem_size: usize,
+    },
+    /// Archive gzip ou zip illisible.
+    Archive(String),
+    /// L'archive zip ne contient aucun fichier.
+    NoRomInArchive,
+    /// L'archive zip contient plusieurs fichiers, la ROM à charger est ambiguë.
+    MultipleRomsInArchive(usize),
+}
+
+/// Raison pour laquelle un remappage .c8k n'a pas pu être lu.
+#[derive(Debug)]
+pub enum KeymapError {
+    Io(io::Error),
+    /// Le fichier n'est pas formé de 16 chiffres hexadécimaux.
+    Invalid(String),
+}
@@ -0,0 +1,38 @@
+use std::time::{Duration, Instant};
+
+use super::schema::{Scheduler, Slice, MAX_CATCH_UP, TIMER_HZ};
+
+impl Scheduler {
+    pub fn new(ips: u32, now: Instant) -> Self {
+        Scheduler {
+            ips,
+            last: now,
+            cpu_budget: 0.0,
+            timer_budget: 0.0,
+        }
+    }
+
+    /// Avance l'horloge jusqu'à `now` et retourne le travail dû. Au-delà de `MAX_CATCH_UP`,
+    /// le retard est abandonné plutôt que rattrapé d'un coup.
+    pub fn advance(&mut self, now: Instant) -> Slice {
+        let elapsed = now.saturating_duration_since(self.last).min(MAX_CATCH_UP);
+        self.last = now;
+
+        self.cpu_budget += elapsed.as_secs_f64() * self.ips as f64;
+        self.timer_budget += elapsed.as_secs_f64() * TIMER_HZ as f64;
+
+        let slice = Slice {
+            instructions: self.cpu_budget as u32,
+            timer_ticks: self.timer_budget as u32,
+        };
+        self.cpu_budget -= slice.instructions as f64;
+        self.timer_budget -= slice.timer_ticks as f64;
+        slice
+    }
+
+    /// Temps restant avant le prochain tick de timer.
+    pub fn until_next_tick(&self) -> Duration {
+        let remaining = (1.0 - self.timer_budget).max(0.0) / TIMER_HZ as f64;
+        Duration::from_secs_f64(remaining)
+    }
+}
@@ -0,0 +1,21 @@
+use std::time::{Duration, Instant};
+
+pub const TIMER_HZ: u32 = 60; // fréquence des timers delay et son
+pub const MAX_CATCH_UP: Duration = Duration::from_millis(100); // retard maximum rattrapé
+
+/// Cadence l'émulation sur une horloge monotone : les instructions à `ips` par seconde
+/// et les timers à exactement 60 Hz, indépendamment du rythme de la boucle d'affichage.
+#[derive(Clone, Debug)]
+pub struct Scheduler {
+    pub ips: u32,
+    pub last: Instant,
+    pub cpu_budget: f64,   // instructions dues, fraction comprise
+    pub timer_budget: f64, // ticks de timer dus, fraction comprise
+}
+
+/// Travail à effectuer pour rattraper l'horloge.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Slice {
+    pub instructions: u32,
+    pub timer_ticks: u32,
+}
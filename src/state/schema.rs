@@ -0,0 +1,31 @@
+use std::io;
+
+pub const STATE_MAGIC: [u8; 4] = *b"C8ST";
+pub const STATE_VERSION: u16 = 5; // à incrémenter à chaque changement du format
+pub const STATE_EXT: &str = "c8s";
+pub const NO_KEY: u8 = 0xFF; // `awaiting_key` vide dans le fichier
+
+/// Raison pour laquelle une sauvegarde d'état n'a pas pu être relue.
+#[derive(Debug)]
+pub enum StateError {
+    Io(io::Error),
+    /// Le fichier ne commence pas par `STATE_MAGIC`.
+    BadMagic,
+    /// Sauvegarde écrite par une autre version du format.
+    UnsupportedVersion(u16),
+    /// Sauvegarde faite avec une autre ROM.
+    RomMismatch {
+        expected: u64,
+        found: u64,
+    },
+    /// Le fichier s'arrête avant la fin de l'état.
+    Truncated,
+    /// Valeur impossible pour la machine (taille mémoire, registre...).
+    Invalid(&'static str),
+}
+
+/// Lecture séquentielle d'une sauvegarde, en petit-boutiste.
+pub struct Reader<'a> {
+    pub data: &'a [u8],
+    pub pos: usize,
+}
@@ -0,0 +1,282 @@
+use std::{
+    fmt, fs, io,
+    path::{Path, PathBuf},
+};
+
+use crate::cpu::schema::{Quirks, RngState, MEM_SIZE, XO_MEM_SIZE};
+use crate::display::schema::{HIRES_H, NBR_PLANES};
+use crate::machine::schema::Machine;
+use crate::rom::schema::STDIN_PATH;
+
+use super::schema::{Reader, StateError, NO_KEY, STATE_EXT, STATE_MAGIC, STATE_VERSION};
+
+/// Empreinte FNV-1a 64 bits de la ROM, pour refuser l'état d'un autre jeu.
+pub fn rom_hash(rom: &[u8]) -> u64 {
+    rom.iter().fold(0xCBF2_9CE4_8422_2325, |hash, &byte| {
+        (hash ^ byte as u64).wrapping_mul(0x0100_0000_01B3)
+    })
+}
+
+/// Fichier de l'emplacement `slot` : `PONG2.1.c8s` pour `PONG2.ch8`.
+/// Une ROM lue sur l'entrée standard n'a pas d'emplacement.
+pub fn slot_path(rom_path: &str, slot: u8) -> Option<PathBuf> {
+    (rom_path != STDIN_PATH)
+        .then(|| PathBuf::from(rom_path).with_extension(format!("{}.{}", slot, STATE_EXT)))
+}
+
+impl Machine {
+    /// Sérialise la machine : en-tête (magic, version, empreinte de la ROM), quirks,
+    /// CPU, générateur de CXNN, écran puis attente de touche. L'audio et les touches
+    /// enfoncées n'en font pas partie.
+    pub fn save_state(&self) -> Vec<u8> {
+        let cpu = &self.cpu;
+        let mut out = Vec::with_capacity(cpu.mem.len() + 8300);
+        out.extend_from_slice(&STATE_MAGIC);
+        out.extend_from_slice(&STATE_VERSION.to_le_bytes());
+        out.extend_from_slice(&self.rom_hash.to_le_bytes());
+
+        out.push(quirk_bits(cpu.quirks));
+        out.extend_from_slice(&(cpu.mem.len() as u32).to_le_bytes());
+        out.extend_from_slice(&cpu.mem);
+        out.extend_from_slice(&cpu.V);
+        out.extend_from_slice(&cpu.I.to_le_bytes());
+        for addr in cpu.stack {
+            out.extend_from_slice(&addr.to_le_bytes());
+        }
+        out.push(cpu.sp);
+        out.push(cpu.game_count);
+        out.push(cpu.sound_count);
+        out.extend_from_slice(&cpu.pc.to_le_bytes());
+        out.extend_from_slice(&cpu.rpl);
+        out.push(cpu.exited as u8);
+        out.push(cpu.audio_pattern.is_some() as u8);
+        out.extend_from_slice(&cpu.audio_pattern.unwrap_or_default());
+        out.push(cpu.pitch);
+        write_rng(&mut out, cpu.rng.snapshot());
+
+        out.push(self.screen.hires as u8);
+        out.push(self.screen.planes);
+        for column in &self.screen.pixel {
+            out.extend_from_slice(column);
+        }
+        out.push(self.keyboard.awaiting_key.unwrap_or(NO_KEY));
+        out
+    }
+
+    /// Restaure un état produit par `save_state` avec la même ROM. La machine n'est
+    /// modifiée que si tout l'état a pu être relu.
+    pub fn load_state(&mut self, data: &[u8]) -> Result<(), StateError> {
+        let mut r = Reader { data, pos: 0 };
+        if r.bytes(STATE_MAGIC.len())? != STATE_MAGIC {
+            return Err(StateError::BadMagic);
+        }
+        let version = r.u16()?;
+        if version != STATE_VERSION {
+            return Err(StateError::UnsupportedVersion(version));
+        }
+        let found = r.u64()?;
+        if found != self.rom_hash {
+            return Err(StateError::RomMismatch {
+                expected: self.rom_hash,
+                found,
+            });
+        }
+
+        let mut cpu = self.cpu.clone();
+        cpu.quirks = quirks_from_bits(r.u8()?);
+        let mem_size = r.u32()? as usize;
+        if mem_size != MEM_SIZE && mem_size != XO_MEM_SIZE {
+            return Err(StateError::Invalid("memory size"));
+        }
+        cpu.mem = r.bytes(mem_size)?.to_vec();
+        cpu.V.copy_from_slice(r.bytes(16)?);
+        cpu.I = r.u16()?;
+        for addr in cpu.stack.iter_mut() {
+            *addr = r.u16()?;
+        }
+        cpu.sp = r.u8()?;
+        if cpu.sp as usize > cpu.stack.len() {
+            return Err(StateError::Invalid("stack pointer"));
+        }
+        cpu.game_count = r.u8()?;
+        cpu.sound_count = r.u8()?;
+        cpu.pc = r.u16()?;
+        cpu.rpl.copy_from_slice(r.bytes(16)?);
+        cpu.exited = r.u8()? != 0;
+        let has_pattern = r.u8()? != 0;
+        let pattern = r.bytes(16)?.try_into().unwrap();
+        cpu.audio_pattern = has_pattern.then_some(pattern);
+        cpu.pitch = r.u8()?;
+        // une source qui ne se rejoue pas (entropie, source externe) reste celle en place
+        if let Some(rng) = read_rng(&mut r)? {
+            cpu.rng = rng.source();
+        }
+
+        let mut screen = self.screen.clone();
+        screen.hires = r.u8()? != 0;
+        screen.planes = r.u8()?;
+        if screen.planes >= 1 << NBR_PLANES {
+            return Err(StateError::Invalid("plane mask"));
+        }
+        for column in screen.pixel.iter_mut() {
+            let pixels = r.bytes(HIRES_H as usize)?;
+            if pixels.iter().any(|&pixel| pixel >= 1 << NBR_PLANES) {
+                return Err(StateError::Invalid("pixel value"));
+            }
+            column.copy_from_slice(pixels);
+        }
+        let awaiting_key = match r.u8()? {
+            NO_KEY => None,
+            key if key < 16 => Some(key),
+            _ => return Err(StateError::Invalid("awaited key register")),
+        };
+
+        self.cpu = cpu;
+        self.screen = screen;
+        self.keyboard.awaiting_key = awaiting_key;
+        if let Some(cache) = &mut self.cache {
+            cache.resize(self.cpu.mem.len());
+        }
+        self.audio.set_tone(self.cpu.tone());
+        self.audio.set_beep(self.cpu.sound_count > 0);
+        Ok(())
+    }
+
+    pub fn save_state_file(&self, path: &Path) -> io::Result<()> {
+        fs::write(path, self.save_state())
+    }
+
+    pub fn load_state_file(&mut self, path: &Path) -> Result<(), StateError> {
+        let data = fs::read(path)?;
+        self.load_state(&data)
+    }
+}
+
+/// Générateur de CXNN : 0 pour une source qui ne se rejoue pas, sinon son type puis son état.
+fn write_rng(out: &mut Vec<u8>, rng: Option<RngState>) {
+    match rng {
+        None => out.push(0),
+        Some(RngState::XorShift(state)) => {
+            out.push(1);
+            out.extend_from_slice(&state.to_le_bytes());
+        }
+        Some(RngState::FontTable { pointer, value }) => {
+            out.extend_from_slice(&[2, pointer, value]);
+        }
+        Some(RngState::Sequence { values, pos }) => {
+            out.push(3);
+            out.extend_from_slice(&(values.len() as u32).to_le_bytes());
+            out.extend_from_slice(&values);
+            out.extend_from_slice(&(pos as u32).to_le_bytes());
+        }
+    }
+}
+
+fn read_rng(r: &mut Reader) -> Result<Option<RngState>, StateError> {
+    let rng = match r.u8()? {
+        0 => return Ok(None),
+        1 => match r.u64()? {
+            0 => return Err(StateError::Invalid("xorshift state")),
+            state => RngState::XorShift(state),
+        },
+        2 => RngState::FontTable {
+            pointer: r.u8()?,
+            value: r.u8()?,
+        },
+        3 => {
+            let len = r.u32()? as usize;
+            let values = r.bytes(len)?.to_vec();
+            let pos = r.u32()? as usize;
+            if pos >= len.max(1) {
+                return Err(StateError::Invalid("sequence position"));
+            }
+            RngState::Sequence { values, pos }
+        }
+        _ => return Err(StateError::Invalid("random source")),
+    };
+    Ok(Some(rng))
+}
+
+fn quirk_bits(quirks: Quirks) -> u8 {
+    [
+        quirks.shift_uses_vy,
+        quirks.load_store_increments_i,
+        quirks.jump_uses_vx,
+        quirks.logic_resets_vf,
+        quirks.clip_sprites,
+        quirks.superchip_opcodes,
+        quirks.xochip_opcodes,
+    ]
+    .iter()
+    .enumerate()
+    .fold(0, |bits, (i, &set)| bits | (set as u8) << i)
+}
+
+fn quirks_from_bits(bits: u8) -> Quirks {
+    let bit = |i: u8| bits & (1 << i) != 0;
+    Quirks {
+        shift_uses_vy: bit(0),
+        load_store_increments_i: bit(1),
+        jump_uses_vx: bit(2),
+        logic_resets_vf: bit(3),
+        clip_sprites: bit(4),
+        superchip_opcodes: bit(5),
+        xochip_opcodes: bit(6),
+    }
+}
+
+impl<'a> Reader<'a> {
+    pub(crate) fn bytes(&mut self, len: usize) -> Result<&'a [u8], StateError> {
+        let bytes = self
+            .data
+            .get(self.pos..self.pos + len)
+            .ok_or(StateError::Truncated)?;
+        self.pos += len;
+        Ok(bytes)
+    }
+
+    pub(crate) fn u8(&mut self) -> Result<u8, StateError> {
+        Ok(self.bytes(1)?[0])
+    }
+
+    pub(crate) fn u16(&mut self) -> Result<u16, StateError> {
+        Ok(u16::from_le_bytes(self.bytes(2)?.try_into().unwrap()))
+    }
+
+    pub(crate) fn u32(&mut self) -> Result<u32, StateError> {
+        Ok(u32::from_le_bytes(self.bytes(4)?.try_into().unwrap()))
+    }
+
+    pub(crate) fn u64(&mut self) -> Result<u64, StateError> {
+        Ok(u64::from_le_bytes(self.bytes(8)?.try_into().unwrap()))
+    }
+}
+
+impl From<io::Error> for StateError {
+    fn from(e: io::Error) -> Self {
+        StateError::Io(e)
+    }
+}
+
+impl fmt::Display for StateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StateError::Io(e) => write!(f, "{}", e),
+            StateError::BadMagic => write!(f, "not a save state file"),
+            StateError::UnsupportedVersion(version) => write!(
+                f,
+                "save state version {} is not supported (expected {})",
+                version, STATE_VERSION
+            ),
+            StateError::RomMismatch { expected, found } => write!(
+                f,
+                "save state was made with another ROM (hash {:016X}, expected {:016X})",
+                found, expected
+            ),
+            StateError::Truncated => write!(f, "save state file is truncated"),
+            StateError::Invalid(what) => write!(f, "save state has an invalid {}", what),
+        }
+    }
+}
+
+impl std::error::Error for StateError {}
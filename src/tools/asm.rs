@@ -0,0 +1,20 @@
+use std::fs;
+
+use chip_8::asm::schema::Assembler;
+
+/// Assemble `source` et écrit le binaire dans `output`.
+pub fn run_asm(source: &str, output: &str, defines: &[String]) -> Result<(), String> {
+    let text = fs::read_to_string(source).map_err(|e| format!("cannot read {}: {}", source, e))?;
+
+    let mut assembler = Assembler::new();
+    for name in defines {
+        assembler.define(name);
+    }
+    let binary = assembler
+        .assemble(&text)
+        .map_err(|e| format!("{}:{}", source, e))?;
+
+    fs::write(output, &binary).map_err(|e| format!("cannot write {}: {}", output, e))?;
+    println!("Assembled {} byte(s) into {}", binary.len(), output);
+    Ok(())
+}
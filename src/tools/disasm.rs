@@ -0,0 +1,30 @@
+use std::fs;
+
+use chip_8::cpu::schema::Profile;
+use chip_8::disasm::schema::Disassembly;
+use chip_8::rom::loader::read_rom;
+
+/// Désassemble `rom` et écrit le source dans `output` ou sur la sortie standard.
+pub fn run_disasm(
+    rom: &str,
+    output: Option<&str>,
+    profile: Profile,
+    load_addr: usize,
+) -> Result<(), String> {
+    let bytes = read_rom(rom).map_err(|e| format!("cannot read {}: {}", rom, e))?;
+    if load_addr + bytes.len() > profile.mem_size() {
+        return Err(format!(
+            "{} does not fit in memory at #{:03X}",
+            rom, load_addr
+        ));
+    }
+
+    let source = Disassembly::analyze(&bytes, load_addr as u16, profile).to_source();
+    match output {
+        Some(path) => fs::write(path, source).map_err(|e| format!("cannot write {}: {}", path, e)),
+        None => {
+            print!("{}", source);
+            Ok(())
+        }
+    }
+}
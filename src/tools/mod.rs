@@ -0,0 +1,3 @@
+pub mod asm;
+pub mod disasm;
+pub mod schema;
@@ -0,0 +1,34 @@
+use chip_8::cpu::schema::Profile;
+use clap::Subcommand;
+
+use crate::parse_addr;
+
+/// Outils en ligne de commande qui ne lancent pas l'émulation.
+#[derive(Subcommand, Debug)]
+pub enum Tool {
+    /// Assemble un source CHIPPER en ROM
+    Asm {
+        /// Fichier source (.src)
+        source: String,
+        /// ROM produite
+        #[arg(short, long)]
+        output: String,
+        /// Définit un symbole pour IFDEF, répétable (par exemple `-D SUPER`)
+        #[arg(short = 'D', long = "define")]
+        defines: Vec<String>,
+    },
+    /// Désassemble une ROM en source CHIPPER
+    Disasm {
+        /// Chemin de la ROM (.ch8, .gz ou .zip), ou `-` pour l'entrée standard
+        rom: String,
+        /// Fichier de sortie, sinon la sortie standard
+        #[arg(short, long)]
+        output: Option<String>,
+        /// Variante visée : vip, chip48, schip, xochip ou modern
+        #[arg(long, default_value = "modern")]
+        profile: Profile,
+        /// Adresse de chargement de la ROM en hexadécimal
+        #[arg(long, default_value = "200", value_parser = parse_addr)]
+        load_addr: usize,
+    },
+}
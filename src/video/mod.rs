@@ -0,0 +1,3 @@
+pub mod schema;
+#[allow(clippy::module_inception)]
+pub mod video;
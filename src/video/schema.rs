@@ -0,0 +1,34 @@
+use std::{fs::File, io::BufWriter, path::PathBuf};
+
+use crate::display::schema::Palette;
+
+pub const DEFAULT_VIDEO_SCALE: u32 = 4; // 512x256 pour la grille haute résolution
+pub const GIF_MIN_DELAY: u64 = 2; // en centièmes, les navigateurs ralentissent les délais plus courts
+
+/// Destination des frames présentées par un frontend, à 60 Hz.
+pub trait FrameSink {
+    /// `pixels` vient d'être affiché et le reste pendant `ticks` frames.
+    fn push(&mut self, pixels: &[u8], ticks: u32) -> std::io::Result<()>;
+    /// Écrit ce qui reste en attente et ferme le fichier.
+    fn finish(&mut self) -> std::io::Result<()>;
+    fn path(&self) -> &PathBuf;
+}
+
+/// GIF animé : les frames identiques successives sont fusionnées, et les délais
+/// (en centièmes de seconde) suivent l'horloge à 60 Hz sans dériver.
+pub struct GifRecorder {
+    pub path: PathBuf,
+    pub encoder: Option<gif::Encoder<BufWriter<File>>>,
+    pub width: u16,
+    pub height: u16,
+    pub pending: Option<Vec<u8>>, // frame affichée, écrite quand l'image change
+    pub ticks: u64,               // frames à 60 Hz depuis le début, frame en attente comprise
+    pub written_cs: u64,          // durée déjà écrite dans le fichier
+}
+
+/// Frames RGB24 brutes mises bout à bout, une par tick, pour un encodeur externe.
+pub struct RawRecorder {
+    pub path: PathBuf,
+    pub out: BufWriter<File>,
+    pub palette: Palette,
+}
@@ -0,0 +1,138 @@
+use std::{
+    borrow::Cow,
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::{Path, PathBuf},
+};
+
+use crate::display::schema::{FrameBuffer, Palette, HIRES_H, HIRES_W};
+
+use super::schema::{FrameSink, GifRecorder, RawRecorder, GIF_MIN_DELAY};
+
+/// Taille des frames enregistrées : la grille haute résolution agrandie `scale` fois,
+/// fixe même quand la ROM change de résolution.
+pub fn frame_size(scale: u32) -> (u32, u32) {
+    let scale = scale.max(1);
+    (HIRES_W * scale, HIRES_H * scale)
+}
+
+impl FrameBuffer {
+    /// Valeurs des pixels ligne par ligne à la taille de `frame_size`, un pixel basse
+    /// résolution couvrant 2x2 cases comme dans la fenêtre.
+    pub fn to_indexed(&self, scale: u32) -> Vec<u8> {
+        let (width, height) = frame_size(scale);
+        let cell = scale.max(1) * HIRES_W / self.width();
+        let mut out = Vec::with_capacity((width * height) as usize);
+        for y in 0..height {
+            for x in 0..width {
+                out.push(self.pixel[(x / cell) as usize][(y / cell) as usize] & 0x3);
+            }
+        }
+        out
+    }
+}
+
+impl GifRecorder {
+    pub fn create(path: &Path, palette: &Palette, scale: u32) -> io::Result<Self> {
+        let (width, height) = frame_size(scale);
+        let (Ok(width), Ok(height)) = (u16::try_from(width), u16::try_from(height)) else {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("{}x{} frames are too large for a GIF", width, height),
+            ));
+        };
+        let out = BufWriter::new(File::create(path)?);
+        let mut encoder = gif::Encoder::new(out, width, height, palette.as_flattened())
+            .map_err(io::Error::other)?;
+        encoder
+            .set_repeat(gif::Repeat::Infinite)
+            .map_err(io::Error::other)?;
+        Ok(GifRecorder {
+            path: path.to_path_buf(),
+            encoder: Some(encoder),
+            width,
+            height,
+            pending: None,
+            ticks: 0,
+            written_cs: 0,
+        })
+    }
+
+    /// Écrit la frame en attente jusqu'à l'instant `ticks`. Trop courte pour un GIF,
+    /// elle est abandonnée et son temps revient à la frame suivante, sauf à la fin.
+    fn flush_pending(&mut self, last: bool) -> io::Result<()> {
+        let (Some(pixels), Some(encoder)) = (self.pending.take(), self.encoder.as_mut()) else {
+            return Ok(());
+        };
+        let end_cs = self.ticks * 100 / 60;
+        let delay = end_cs.saturating_sub(self.written_cs);
+        if delay < GIF_MIN_DELAY && !last {
+            return Ok(());
+        }
+        let frame = gif::Frame {
+            width: self.width,
+            height: self.height,
+            delay: delay.clamp(1, u16::MAX as u64) as u16,
+            buffer: Cow::Owned(pixels),
+            ..Default::default()
+        };
+        encoder.write_frame(&frame).map_err(io::Error::other)?;
+        self.written_cs = end_cs;
+        Ok(())
+    }
+}
+
+impl FrameSink for GifRecorder {
+    fn push(&mut self, pixels: &[u8], ticks: u32) -> io::Result<()> {
+        if self.pending.as_deref() != Some(pixels) {
+            self.flush_pending(false)?;
+            self.pending = Some(pixels.to_vec());
+        }
+        self.ticks += ticks as u64;
+        Ok(())
+    }
+
+    fn finish(&mut self) -> io::Result<()> {
+        self.flush_pending(true)?;
+        match self.encoder.take() {
+            Some(encoder) => encoder.into_inner()?.flush(),
+            None => Ok(()),
+        }
+    }
+
+    fn path(&self) -> &PathBuf {
+        &self.path
+    }
+}
+
+impl RawRecorder {
+    pub fn create(path: &Path, palette: &Palette) -> io::Result<Self> {
+        Ok(RawRecorder {
+            path: path.to_path_buf(),
+            out: BufWriter::new(File::create(path)?),
+            palette: *palette,
+        })
+    }
+}
+
+impl FrameSink for RawRecorder {
+    fn push(&mut self, pixels: &[u8], ticks: u32) -> io::Result<()> {
+        let rgb: Vec<u8> = pixels
+            .iter()
+            .flat_map(|&value| self.palette[value as usize])
+            .collect();
+        // une frame par tick, le flux reste à 60 images par seconde
+        for _ in 0..ticks {
+            self.out.write_all(&rgb)?;
+        }
+        Ok(())
+    }
+
+    fn finish(&mut self) -> io::Result<()> {
+        self.out.flush()
+    }
+
+    fn path(&self) -> &PathBuf {
+        &self.path
+    }
+}
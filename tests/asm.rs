@@ -0,0 +1,38 @@
+use std::fs;
+
+use chip_8::asm::schema::Assembler;
+
+/// Chaque source CHIPPER livrée doit redonner la ROM de `games/` octet pour octet.
+/// VBRIX.SRC est écrit pour un autre assembleur (voir le README).
+#[test]
+fn shipped_sources_reassemble_byte_for_byte() {
+    let mut checked = 0;
+    for entry in fs::read_dir("games/sources").unwrap() {
+        let path = entry.unwrap().path();
+        let stem = path.file_stem().unwrap().to_str().unwrap().to_string();
+        if stem == "VBRIX" {
+            continue;
+        }
+        let source = fs::read_to_string(&path).unwrap();
+        let binary = Assembler::new()
+            .assemble(&source)
+            .unwrap_or_else(|e| panic!("{}: {}", path.display(), e));
+        let shipped = fs::read(format!("games/{}.ch8", stem)).unwrap();
+        assert!(
+            binary == shipped,
+            "{} differs from games/{}.ch8",
+            path.display(),
+            stem
+        );
+        checked += 1;
+    }
+    assert_eq!(checked, 9);
+}
+
+#[test]
+fn errors_report_the_line_number() {
+    let error = Assembler::new()
+        .assemble("CLS\nLD V0, #12\nBOGUS V1\n")
+        .unwrap_err();
+    assert_eq!(error.line, 3, "{}", error);
+}
@@ -0,0 +1,40 @@
+//! Partie de référence partagée par les tests d'états, de rembobinage et de films.
+#![allow(dead_code)]
+
+use std::fs;
+
+use chip_8::cpu::schema::{RngKind, START_ADRR};
+use chip_8::machine::schema::Machine;
+
+pub const SPEED: usize = 10;
+/// BRIX tire des nombres au hasard, le générateur de CXNN doit suivre la partie.
+pub const GAME: &str = "BRIX";
+
+/// Charge `games/<name>.ch8` avec un générateur rejouable et joue ses premières frames.
+pub fn game(name: &str, seed: u64) -> Machine {
+    let rom = fs::read(format!("games/{}.ch8", name)).unwrap();
+    let mut machine = Machine::new(false, Default::default());
+    machine.load_rom(&rom, START_ADRR).unwrap();
+    machine.cpu.rng = RngKind::XorShift.source(seed);
+    play(&mut machine, 60);
+    machine
+}
+
+/// Joue `frames` frames en allant à gauche (4) puis à droite (6).
+pub fn play(machine: &mut Machine, frames: usize) {
+    for n in 0..frames {
+        frame(machine, n);
+    }
+}
+
+/// Joue la frame numéro `n` de la partie.
+pub fn frame(machine: &mut Machine, n: usize) {
+    press(machine, n);
+    machine.run_frame(SPEED).unwrap();
+}
+
+/// Touches tenues pendant la frame numéro `n`.
+pub fn press(machine: &mut Machine, n: usize) {
+    machine.keyboard.keys[4] = n % 40 < 20;
+    machine.keyboard.keys[6] = n % 40 >= 20;
+}
@@ -0,0 +1,79 @@
+use chip_8::cpu::schema::{DecodeCache, Instruction};
+
+#[test]
+fn encode_inverts_decode_for_every_opcode() {
+    for opcode in 0..=u16::MAX {
+        let instruction = Instruction::decode(opcode);
+        assert_eq!(
+            instruction.encode(),
+            opcode,
+            "{:04X} decoded to {:?}",
+            opcode,
+            instruction
+        );
+        assert_eq!(Instruction::decode(instruction.encode()), instruction);
+    }
+}
+
+#[test]
+fn decodes_fields_from_nibbles() {
+    assert_eq!(
+        Instruction::decode(0xD125),
+        Instruction::Drw { x: 1, y: 2, n: 5 }
+    );
+    assert_eq!(
+        Instruction::decode(0x7A3C),
+        Instruction::AddImm { x: 0xA, kk: 0x3C }
+    );
+    assert_eq!(
+        Instruction::decode(0x2ABC),
+        Instruction::Call { nnn: 0xABC }
+    );
+    assert_eq!(
+        Instruction::decode(0x00C4),
+        Instruction::ScrollDown { n: 4 }
+    );
+    assert_eq!(Instruction::decode(0x9121), Instruction::Unknown(0x9121));
+}
+
+#[test]
+fn cache_follows_rewritten_memory() {
+    let mut cache = DecodeCache::new(0x1000);
+    let mut mem = vec![0u8; 0x1000];
+    mem[0x200..0x204].copy_from_slice(&[0x60, 0x01, 0x12, 0x00]);
+    cache.predecode(&mem, 0x200, mem.len());
+    assert_eq!(cache.get(0x200, 0x6001), Instruction::LdImm { x: 0, kk: 1 });
+
+    // code automodifié : l'opcode lu ne correspond plus à l'entrée en cache
+    assert_eq!(
+        cache.get(0x200, 0x7005),
+        Instruction::AddImm { x: 0, kk: 5 }
+    );
+    assert_eq!(
+        cache.get(0x200, 0x7005),
+        Instruction::AddImm { x: 0, kk: 5 }
+    );
+}
+
+#[test]
+fn addresses_outside_the_cache_are_decoded_directly() {
+    let mut cache = DecodeCache::new(0x1000);
+    assert_eq!(cache.get(0xFFFE, 0x00E0), Instruction::Cls);
+}
+
+#[test]
+fn resized_cache_keeps_its_entries() {
+    let mut cache = DecodeCache::new(0x1000);
+    let mut mem = vec![0u8; 0x1000];
+    mem[0x200..0x202].copy_from_slice(&[0x60, 0x01]);
+    cache.predecode(&mem, 0x200, mem.len());
+
+    cache.resize(0x10000);
+    assert_eq!(cache.entries.len(), 0x10000);
+    assert_eq!(
+        cache.entries[0x200],
+        Some((0x6001, Instruction::LdImm { x: 0, kk: 1 }))
+    );
+    assert_eq!(cache.get(0xFFFE, 0x00E0), Instruction::Cls);
+    assert!(cache.entries[0xFFFE].is_some());
+}
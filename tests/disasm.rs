@@ -0,0 +1,76 @@
+use std::fs;
+
+use chip_8::asm::schema::Assembler;
+use chip_8::cpu::schema::{Profile, START_ADRR};
+use chip_8::disasm::schema::Disassembly;
+
+const PROFILES: [Profile; 5] = [
+    Profile::CosmacVip,
+    Profile::Chip48,
+    Profile::SuperChip,
+    Profile::XoChip,
+    Profile::Modern,
+];
+
+/// Le source produit par `disasm` doit se réassembler en la ROM d'origine, quel que
+/// soit le profil choisi pour séparer le code des données.
+#[test]
+fn every_rom_round_trips_through_asm_for_every_profile() {
+    let mut roms: Vec<_> = ["games", "games/test"]
+        .iter()
+        .flat_map(|dir| fs::read_dir(dir).unwrap())
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "ch8"))
+        .collect();
+    roms.sort();
+    assert!(!roms.is_empty());
+
+    for path in &roms {
+        let rom = fs::read(path).unwrap();
+        for profile in PROFILES {
+            let source = Disassembly::analyze(&rom, START_ADRR as u16, profile).to_source();
+            let binary = Assembler::new()
+                .assemble(&source)
+                .unwrap_or_else(|e| panic!("{} ({:?}): {}", path.display(), profile, e));
+            assert!(
+                binary == rom,
+                "{} ({:?}) does not reassemble to the same bytes",
+                path.display(),
+                profile
+            );
+        }
+    }
+}
+
+#[test]
+fn opcodes_unknown_to_the_profile_become_data() {
+    // 00FF (HIGH) puis un saut sur place
+    let rom = [0x00, 0xFF, 0x12, 0x02];
+    let vip = Disassembly::analyze(&rom, START_ADRR as u16, Profile::CosmacVip).to_source();
+    let schip = Disassembly::analyze(&rom, START_ADRR as u16, Profile::SuperChip).to_source();
+    assert!(!vip.contains("HIGH"), "{}", vip);
+    assert!(schip.contains("HIGH"), "{}", schip);
+}
+
+#[test]
+fn sprites_pointed_by_i_get_a_data_label() {
+    // LD I, 206 ; DRW V0, V0, 1 ; JP 204 ; sprite F0
+    let rom = [0xA2, 0x06, 0xD0, 0x01, 0x12, 0x04, 0xF0];
+    let source = Disassembly::analyze(&rom, START_ADRR as u16, Profile::Modern).to_source();
+    assert!(source.contains("DATA_206:"), "{}", source);
+    assert!(source.contains("LBL_204:"), "{}", source);
+    assert!(source.contains("DB $1111...."), "{}", source);
+}
+
+#[test]
+fn image_filling_the_address_space_is_disassembled_to_the_end() {
+    // LD I, long FFFE ; JP 204 ; zéros jusqu'à la dernière adresse
+    let mut rom = vec![0; 0x10000 - START_ADRR];
+    rom[..6].copy_from_slice(&[0xF0, 0x00, 0xFF, 0xFE, 0x12, 0x04]);
+    let len = rom.len();
+    rom[len - 2..].copy_from_slice(&[0xAB, 0xCD]);
+
+    let source = Disassembly::analyze(&rom, START_ADRR as u16, Profile::XoChip).to_source();
+    assert!(source.contains("DATA_FFFE:"), "{}", &source[..200]);
+    assert!(source.ends_with("    DB $1.1.1.11    ; #AB\n    DB $11..11.1    ; #CD\n"));
+}
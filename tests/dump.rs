@@ -0,0 +1,65 @@
+use chip_8::display::schema::{FrameBuffer, DEFAULT_PALETTE, WHITE};
+
+/// Écran basse résolution avec un pixel allumé en haut à gauche et un pixel du plan 2
+/// à sa droite.
+fn screen() -> FrameBuffer {
+    let mut screen = FrameBuffer::new();
+    screen.pixel[0][0] = WHITE;
+    screen.pixel[1][0] = 2;
+    screen
+}
+
+#[test]
+fn pbm_repeats_each_pixel_scale_times() {
+    let mut out = Vec::new();
+    screen().write_pbm(&mut out, 2).unwrap();
+    let text = String::from_utf8(out).unwrap();
+    let lines: Vec<&str> = text.lines().collect();
+
+    assert_eq!(lines[..2], ["P1", "128 64"]);
+    assert_eq!(lines.len(), 2 + 64);
+    assert!(lines[2].starts_with("1 1 1 1 0 0"), "{}", lines[2]);
+    assert_eq!(lines[3], lines[2]);
+    assert!(lines[4].starts_with("0 0 0 0"), "{}", lines[4]);
+}
+
+#[test]
+fn png_uses_the_palette_at_the_requested_scale() {
+    let mut out = Vec::new();
+    screen().write_png(&mut out, &DEFAULT_PALETTE, 3).unwrap();
+
+    let mut reader = png::Decoder::new(&out[..]).read_info().unwrap();
+    let mut data = vec![0; reader.output_buffer_size()];
+    let info = reader.next_frame(&mut data).unwrap();
+    assert_eq!((info.width, info.height), (64 * 3, 32 * 3));
+    assert_eq!(info.color_type, png::ColorType::Rgb);
+
+    let rgb = |x: usize, y: usize| {
+        let offset = (y * info.width as usize + x) * 3;
+        [data[offset], data[offset + 1], data[offset + 2]]
+    };
+    assert_eq!(rgb(2, 2), DEFAULT_PALETTE[1]);
+    assert_eq!(rgb(3, 0), DEFAULT_PALETTE[2]);
+    assert_eq!(rgb(5, 2), DEFAULT_PALETTE[2]);
+    assert_eq!(rgb(6, 0), DEFAULT_PALETTE[0]);
+    assert_eq!(rgb(0, 3), DEFAULT_PALETTE[0]);
+}
+
+#[test]
+fn scale_zero_is_treated_as_one() {
+    let mut out = Vec::new();
+    screen().write_pbm(&mut out, 0).unwrap();
+    assert!(String::from_utf8(out)
+        .unwrap()
+        .starts_with("P1\n64 32\n1 1 0"));
+}
+
+#[test]
+fn oversized_png_is_an_error() {
+    let mut out = Vec::new();
+    let error = screen()
+        .write_png(&mut out, &DEFAULT_PALETTE, u32::MAX / 32)
+        .unwrap_err();
+    assert_eq!(error.kind(), std::io::ErrorKind::InvalidInput);
+    assert!(out.is_empty());
+}
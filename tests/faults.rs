@@ -0,0 +1,178 @@
+use std::process::{Command, Stdio};
+
+use chip_8::cpu::schema::{Chip8Error, START_ADRR};
+use chip_8::machine::schema::{ErrorPolicy, Machine};
+
+fn machine(rom: &[u8]) -> Machine {
+    let mut machine = Machine::new(false, Default::default());
+    machine.cpu.mem[START_ADRR..START_ADRR + rom.len()].copy_from_slice(rom);
+    machine
+}
+
+#[test]
+fn stack_overflow_after_sixteen_calls() {
+    // 2200 s'appelle lui-même
+    let mut machine = machine(&[0x22, 0x00]);
+    for _ in 0..16 {
+        machine.step().unwrap();
+    }
+    assert_eq!(
+        machine.step(),
+        Err(Chip8Error::StackOverflow {
+            pc: 0x200,
+            opcode: 0x2200
+        })
+    );
+}
+
+#[test]
+fn stack_underflow_on_return_without_call() {
+    let mut machine = machine(&[0x00, 0xEE]);
+    assert_eq!(
+        machine.step(),
+        Err(Chip8Error::StackUnderflow {
+            pc: 0x200,
+            opcode: 0x00EE
+        })
+    );
+}
+
+#[test]
+fn unknown_opcode_leaves_pc_on_the_instruction() {
+    let mut machine = machine(&[0x5A, 0xB7]);
+    let error = machine.step().unwrap_err();
+    assert_eq!(
+        error,
+        Chip8Error::UnknownOpcode {
+            pc: 0x200,
+            opcode: 0x5AB7
+        }
+    );
+    assert_eq!(machine.cpu.pc, 0x200);
+    assert_eq!(
+        error.to_string(),
+        "unknown opcode at PC=0x200 (opcode 5AB7)"
+    );
+}
+
+#[test]
+fn memory_fault_reports_the_first_invalid_address() {
+    // I = FFE puis FX65 sur trois registres : FFE et FFF existent, 1000 non
+    let mut machine = machine(&[0xAF, 0xFE, 0xF2, 0x65]);
+    machine.step().unwrap();
+    assert_eq!(
+        machine.step(),
+        Err(Chip8Error::MemoryOutOfBounds {
+            pc: 0x202,
+            opcode: 0xF265,
+            addr: 0x1000
+        })
+    );
+}
+
+#[test]
+fn fetch_fault_keeps_the_partial_opcode() {
+    let mut machine = machine(&[0x00, 0xE0]);
+    machine.cpu.mem[0xFFF] = 0x12;
+    machine.cpu.pc = 0xFFF;
+    let error = machine.step().unwrap_err();
+    assert_eq!(
+        error,
+        Chip8Error::FetchOutOfBounds {
+            pc: 0xFFF,
+            partial: Some(0x12)
+        }
+    );
+    assert_eq!(error.opcode(), None);
+    assert!(error.to_string().contains("partial opcode 12"), "{}", error);
+
+    machine.cpu.pc = 0x1000;
+    assert_eq!(
+        machine.step(),
+        Err(Chip8Error::FetchOutOfBounds {
+            pc: 0x1000,
+            partial: None
+        })
+    );
+}
+
+#[test]
+fn error_policy_skips_the_faulting_instruction() {
+    // 00EE fautif puis V0 = 07
+    for policy in [ErrorPolicy::Log, ErrorPolicy::Ignore] {
+        let mut machine = machine(&[0x00, 0xEE, 0x60, 0x07]);
+        machine.on_error = policy;
+        machine.run_instruction().unwrap();
+        machine.run_instruction().unwrap();
+        assert_eq!(machine.cpu.V[0], 7);
+    }
+
+    let mut machine = machine(&[0x00, 0xEE]);
+    assert!(machine.run_instruction().is_err());
+    assert_eq!(machine.cpu.pc, 0x200);
+}
+
+#[test]
+fn fetch_faults_halt_whatever_the_policy() {
+    for policy in [ErrorPolicy::Halt, ErrorPolicy::Log, ErrorPolicy::Ignore] {
+        let mut machine = machine(&[0x00, 0xE0]);
+        machine.on_error = policy;
+        machine.cpu.pc = 0xFFE;
+        machine.cpu.mem[0xFFE..].copy_from_slice(&[0x60, 0x01]);
+        machine.run_instruction().unwrap();
+        assert!(
+            matches!(
+                machine.run_frame(10),
+                Err(Chip8Error::FetchOutOfBounds { pc: 0x1000, .. })
+            ),
+            "{:?}",
+            policy
+        );
+        assert_eq!(machine.cpu.pc, 0x1000, "{:?}", policy);
+    }
+}
+
+#[test]
+fn sprite_past_the_end_of_memory_is_a_fault() {
+    // I = FFC puis D005 : les quatre derniers octets existent, le cinquième non
+    let mut machine = machine(&[0xAF, 0xFC, 0xD0, 0x05, 0x60, 0x07]);
+    machine.cpu.mem[0xFFC..].copy_from_slice(&[0xFF; 4]);
+    machine.step().unwrap();
+    assert_eq!(
+        machine.step(),
+        Err(Chip8Error::MemoryOutOfBounds {
+            pc: 0x202,
+            opcode: 0xD005,
+            addr: 0x1000
+        })
+    );
+    assert!(machine.screen.pixel.iter().flatten().all(|&p| p == 0));
+
+    machine.on_error = ErrorPolicy::Log;
+    machine.run_instruction().unwrap();
+    machine.run_instruction().unwrap();
+    assert_eq!(machine.cpu.V[0], 7);
+}
+
+#[test]
+fn log_watches_do_not_pause_on_a_fault() {
+    // 00EE sans appel : avec seulement un --watch log, la faute arrête l'émulation
+    let path = std::env::temp_dir().join(format!("chip8-fault-{}.ch8", std::process::id()));
+    std::fs::write(&path, [0x60, 0x01, 0x00, 0xEE]).unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_chip-8"))
+        .arg(&path)
+        .args(["--headless", "--watch", "V0 log"])
+        .stdin(Stdio::null())
+        .output()
+        .unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("emulation stopped: stack underflow"),
+        "{}",
+        stderr
+    );
+}
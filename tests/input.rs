@@ -0,0 +1,134 @@
+use chip_8::cpu::schema::Keyboard;
+use chip_8::input::schema::{
+    Axis, Button, Control, GamepadMapping, Input, InputEvent, DEFAULT_THRESHOLD,
+};
+
+fn mapping(pairs: &[(&str, u8)]) -> GamepadMapping {
+    GamepadMapping {
+        bindings: pairs
+            .iter()
+            .map(|(name, key)| (name.parse().unwrap(), *key))
+            .collect(),
+    }
+}
+
+fn button(player: u8, button: Button, pressed: bool) -> InputEvent {
+    InputEvent::Button {
+        player,
+        button,
+        pressed,
+    }
+}
+
+fn axis(player: u8, axis: Axis, value: i16) -> InputEvent {
+    InputEvent::Axis {
+        player,
+        axis,
+        value,
+    }
+}
+
+#[test]
+fn stick_counts_only_past_the_threshold() {
+    let mut input = Input::new(
+        [GamepadMapping::standard(), GamepadMapping::default()],
+        DEFAULT_THRESHOLD,
+    );
+    let mut keyboard = Keyboard::new();
+
+    input.handle(axis(0, Axis::LeftX, DEFAULT_THRESHOLD), &mut keyboard);
+    assert!(!keyboard.ispressed(9));
+    input.handle(axis(0, Axis::LeftX, DEFAULT_THRESHOLD + 1), &mut keyboard);
+    assert!(keyboard.ispressed(9));
+
+    // passer d'un côté à l'autre relâche la première touche
+    input.handle(axis(0, Axis::LeftX, -32768), &mut keyboard);
+    assert!(!keyboard.ispressed(9));
+    assert!(keyboard.ispressed(7));
+    input.handle(axis(0, Axis::LeftX, 0), &mut keyboard);
+    assert_eq!(keyboard.keys, [false; 16]);
+}
+
+#[test]
+fn each_player_has_its_own_mapping() {
+    let mut input = Input::new(
+        [mapping(&[("a", 0x1)]), mapping(&[("a", 0x2)])],
+        DEFAULT_THRESHOLD,
+    );
+    let mut keyboard = Keyboard::new();
+
+    input.handle(button(1, Button::A, true), &mut keyboard);
+    assert!(keyboard.ispressed(2));
+    assert!(!keyboard.ispressed(1));
+
+    input.handle(button(0, Button::A, true), &mut keyboard);
+    input.handle(button(1, Button::A, false), &mut keyboard);
+    assert!(keyboard.ispressed(1));
+    assert!(!keyboard.ispressed(2));
+
+    // une troisième manette n'a pas d'association
+    input.handle(button(2, Button::A, true), &mut keyboard);
+    assert_eq!(input.held.len(), 1);
+}
+
+#[test]
+fn key_stays_down_while_any_source_holds_it() {
+    let mut input = Input::new(
+        [GamepadMapping::standard(), GamepadMapping::default()],
+        DEFAULT_THRESHOLD,
+    );
+    let mut keyboard = Keyboard::new();
+    let key = |pressed| InputEvent::Key {
+        code: 119,
+        key: 5,
+        pressed,
+    };
+
+    input.handle(key(true), &mut keyboard);
+    input.handle(button(0, Button::DPadUp, true), &mut keyboard);
+    input.handle(key(false), &mut keyboard);
+    assert!(keyboard.ispressed(5));
+    input.handle(button(0, Button::DPadUp, false), &mut keyboard);
+    assert!(!keyboard.ispressed(5));
+}
+
+#[test]
+fn disconnected_players_release_their_keys() {
+    let mut input = Input::new(
+        [GamepadMapping::standard(), GamepadMapping::standard()],
+        DEFAULT_THRESHOLD,
+    );
+    let mut keyboard = Keyboard::new();
+
+    input.handle(button(0, Button::A, true), &mut keyboard);
+    input.handle(axis(0, Axis::LeftY, i16::MAX), &mut keyboard);
+    input.handle(button(1, Button::B, true), &mut keyboard);
+    input.release_player(0, &mut keyboard);
+
+    assert!(!keyboard.ispressed(6));
+    assert!(!keyboard.ispressed(8));
+    assert!(keyboard.ispressed(4));
+}
+
+#[test]
+fn controls_parse_buttons_and_axis_directions() {
+    assert_eq!(
+        "DPUP".parse::<Control>(),
+        Ok(Control::Button(Button::DPadUp))
+    );
+    assert_eq!(
+        "leftx-".parse::<Control>(),
+        Ok(Control::Axis {
+            axis: Axis::LeftX,
+            positive: false
+        })
+    );
+    assert_eq!(
+        "lefttrigger".parse::<Control>(),
+        Ok(Control::Axis {
+            axis: Axis::TriggerLeft,
+            positive: true
+        })
+    );
+    assert!("turbo".parse::<Control>().is_err());
+}
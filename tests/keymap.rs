@@ -0,0 +1,57 @@
+use std::path::Path;
+
+use chip_8::cpu::schema::{Keyboard, Keymap};
+use chip_8::rom::keymap::{find_keymap, read_keymap};
+use chip_8::rom::schema::KeymapError;
+
+#[test]
+fn shipped_keymap_parses() {
+    let keymap = read_keymap(Path::new("games/test/Rocket2.c8k")).unwrap();
+    assert_eq!(keymap.keys[0xF], 5);
+    assert_eq!(
+        find_keymap("games/test/Rocket2.ch8").as_deref(),
+        Some(Path::new("games/test/Rocket2.c8k"))
+    );
+    assert_eq!(find_keymap("-"), None);
+}
+
+#[test]
+fn missing_keymap_files_are_io_errors() {
+    let error = read_keymap(Path::new("games/test/MISSING.c8k")).unwrap_err();
+    assert!(matches!(error, KeymapError::Io(_)), "{:?}", error);
+}
+
+#[test]
+fn whitespace_between_digits_is_ignored() {
+    let keymap: Keymap = "0123 4567\n89AB CDEF\n".parse().unwrap();
+    assert_eq!(keymap, Keymap::IDENTITY);
+}
+
+#[test]
+fn keymaps_need_exactly_sixteen_digits() {
+    for text in ["", "0123456789ABCDE", "0123456789ABCDEF0"] {
+        let error = text.parse::<Keymap>().unwrap_err();
+        assert!(matches!(error, KeymapError::Invalid(_)), "{:?}", text);
+    }
+}
+
+#[test]
+fn keymaps_reject_non_hex_digits() {
+    let error = "0123456789ABCDEG".parse::<Keymap>().unwrap_err();
+    assert_eq!(error.to_string(), "invalid keymap: 'G' is not a hex digit");
+}
+
+#[test]
+fn one_pad_key_can_trigger_several_chip8_keys() {
+    let mut keyboard = Keyboard::new();
+    keyboard.set_keymap("0123456789ABCDE5".parse().unwrap());
+
+    keyboard.set_key(5, true);
+    assert!(keyboard.ispressed(5));
+    assert!(keyboard.ispressed(0xF));
+
+    // la touche F du pavé ne déclenche plus rien
+    keyboard.set_key(5, false);
+    keyboard.set_key(0xF, true);
+    assert_eq!(keyboard.keys, [false; 16]);
+}
@@ -0,0 +1,111 @@
+use std::io::{Cursor, Write};
+use std::process::{Command, Stdio};
+
+use chip_8::cpu::schema::{Profile, START_ADRR};
+use chip_8::machine::schema::Machine;
+use chip_8::rom::loader::unpack_rom;
+use chip_8::rom::schema::{LoadError, ETI660_ADRR};
+use flate2::{write::GzEncoder, Compression};
+use zip::{write::FileOptions, ZipWriter};
+
+/// Dessine le chiffre 0 de la police en haut à gauche puis boucle.
+const ROM: [u8; 6] = [0xA0, 0x00, 0xD0, 0x05, 0x12, 0x04];
+
+fn zip(entries: &[(&str, &[u8])]) -> Vec<u8> {
+    let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+    for (name, data) in entries {
+        writer.start_file(*name, FileOptions::default()).unwrap();
+        writer.write_all(data).unwrap();
+    }
+    writer.finish().unwrap().into_inner()
+}
+
+#[test]
+fn raw_roms_are_returned_as_is() {
+    assert_eq!(unpack_rom(ROM.to_vec()).unwrap(), ROM);
+    assert!(matches!(unpack_rom(Vec::new()), Err(LoadError::Empty)));
+}
+
+#[test]
+fn gzip_roms_are_decompressed() {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(&ROM).unwrap();
+    assert_eq!(unpack_rom(encoder.finish().unwrap()).unwrap(), ROM);
+}
+
+#[test]
+fn zip_with_a_single_rom_is_extracted() {
+    let archive = zip(&[("GAME.ch8", &ROM)]);
+    assert_eq!(unpack_rom(archive).unwrap(), ROM);
+}
+
+#[test]
+fn zip_with_several_entries_is_refused() {
+    let archive = zip(&[("GAME.ch8", &ROM), ("README.txt", b"notes")]);
+    assert!(matches!(
+        unpack_rom(archive),
+        Err(LoadError::MultipleRomsInArchive(2))
+    ));
+}
+
+#[test]
+fn zip_without_files_is_refused() {
+    let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+    writer
+        .add_directory("roms", FileOptions::default())
+        .unwrap();
+    let archive = writer.finish().unwrap().into_inner();
+    assert!(matches!(
+        unpack_rom(archive),
+        Err(LoadError::NoRomInArchive)
+    ));
+}
+
+#[test]
+fn roms_load_at_the_requested_address() {
+    let mut machine = Machine::new(false, Default::default());
+    machine.load_rom(&ROM, ETI660_ADRR).unwrap();
+    assert_eq!(machine.cpu.pc, ETI660_ADRR as u16);
+    assert_eq!(&machine.cpu.mem[ETI660_ADRR..ETI660_ADRR + ROM.len()], ROM);
+    assert_eq!(machine.cpu.mem[START_ADRR], 0);
+
+    assert!(matches!(
+        machine.load_rom(&ROM, 0x1000),
+        Err(LoadError::BadLoadAddress {
+            addr: 0x1000,
+            mem_size: 0x1000
+        })
+    ));
+}
+
+#[test]
+fn roms_too_large_for_memory_are_refused() {
+    let mut machine = Machine::new(false, Default::default());
+    let rom = vec![0x12; 0x1000 - START_ADRR + 1];
+    assert!(matches!(
+        machine.load_rom(&rom, START_ADRR),
+        Err(LoadError::TooLarge { size, max: 0xE00 }) if size == rom.len()
+    ));
+
+    // la même ROM tient dans les 64 Ko de XO-CHIP
+    machine.cpu.set_mem_size(Profile::XoChip.mem_size());
+    machine.load_rom(&rom, START_ADRR).unwrap();
+}
+
+#[test]
+fn roms_are_read_from_stdin() {
+    let dump = std::env::temp_dir().join(format!("chip8-stdin-{}.txt", std::process::id()));
+    let mut child = Command::new(env!("CARGO_BIN_EXE_chip-8"))
+        .args(["-", "--headless", "--frames", "1", "--dump"])
+        .arg(&dump)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .spawn()
+        .unwrap();
+    child.stdin.take().unwrap().write_all(&ROM).unwrap();
+    assert!(child.wait().unwrap().success());
+
+    let screen = std::fs::read_to_string(&dump).unwrap();
+    std::fs::remove_file(&dump).unwrap();
+    assert!(screen.starts_with("####...."), "{}", screen);
+}
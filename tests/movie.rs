@@ -0,0 +1,77 @@
+mod common;
+
+use chip_8::cpu::schema::RngKind;
+use chip_8::machine::schema::Machine;
+use chip_8::movie::schema::{Movie, MovieError, Session};
+
+use common::{game, press, GAME, SPEED};
+
+/// Enregistre 240 frames de la partie de référence, retourne le film et l'état final.
+fn record(rng: &RngKind, seed: u64) -> (Movie, Vec<u8>) {
+    let mut machine = game(GAME, 0);
+    let mut session = Session::Record(Movie::start(&mut machine, SPEED as u32, rng, seed));
+    for n in 0..240 {
+        press(&mut machine, n);
+        assert!(session.run_frame(&mut machine).unwrap());
+    }
+    let Session::Record(movie) = session else {
+        unreachable!()
+    };
+    (movie, machine.save_state())
+}
+
+fn replay(movie: Movie, machine: &mut Machine) -> usize {
+    movie.rewind(machine).unwrap();
+    let mut session = Session::Replay { movie, frame: 0 };
+    let mut frames = 0;
+    while session.run_frame(machine).unwrap() {
+        frames += 1;
+    }
+    frames
+}
+
+#[test]
+fn replay_reaches_the_recorded_state() {
+    for rng in [
+        RngKind::Os,
+        RngKind::XorShift,
+        RngKind::FontTable,
+        RngKind::Sequence(vec![1, 2, 3]),
+    ] {
+        let (movie, expected) = record(&rng, 1234);
+        let bytes = movie.to_bytes();
+        let movie = Movie::from_bytes(&bytes).unwrap();
+        assert_eq!(movie.to_bytes(), bytes);
+        assert_eq!(movie.rng, rng.replayable());
+
+        // une machine partie d'ailleurs est ramenée au début du film
+        let mut machine = game(GAME, 0);
+        machine.run_frame(SPEED).unwrap();
+        assert_eq!(replay(movie, &mut machine), 240);
+        assert!(machine.save_state() == expected, "{}", rng);
+    }
+}
+
+#[test]
+fn movie_from_another_rom_is_refused() {
+    let (movie, _) = record(&RngKind::XorShift, 1);
+    let mut machine = game("PONG", 0);
+    assert!(matches!(
+        movie.rewind(&mut machine),
+        Err(MovieError::State(_))
+    ));
+}
+
+#[test]
+fn damaged_movies_are_refused() {
+    let (movie, _) = record(&RngKind::XorShift, 1);
+    let bytes = movie.to_bytes();
+    assert!(matches!(
+        Movie::from_bytes(b"XXXX"),
+        Err(MovieError::BadMagic)
+    ));
+    assert!(matches!(
+        Movie::from_bytes(&bytes[..bytes.len() - 1]),
+        Err(MovieError::Truncated)
+    ));
+}
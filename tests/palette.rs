@@ -0,0 +1,51 @@
+use chip_8::display::palette::{parse_color, parse_palette};
+use chip_8::display::schema::{AMBER_PALETTE, OCTO_PALETTE, PALETTE_PRESETS};
+
+#[test]
+fn presets_are_found_by_name() {
+    assert_eq!(parse_palette("amber"), Ok(AMBER_PALETTE));
+    assert_eq!(parse_palette(" OCTO "), Ok(OCTO_PALETTE));
+    for (name, palette) in PALETTE_PRESETS {
+        assert_eq!(parse_palette(name), Ok(palette), "{}", name);
+    }
+}
+
+#[test]
+fn two_colors_fill_the_planes_with_intermediate_shades() {
+    assert_eq!(
+        parse_palette("#000000,#FFB000"),
+        Ok([
+            [0x00, 0x00, 0x00],
+            [0xFF, 0xB0, 0x00],
+            [0x55, 0x3A, 0x00],
+            [0xAA, 0x75, 0x00],
+        ])
+    );
+}
+
+#[test]
+fn four_colors_are_taken_as_is() {
+    assert_eq!(
+        parse_palette("102030, #405060,#708090 ,A0B0C0"),
+        Ok([
+            [0x10, 0x20, 0x30],
+            [0x40, 0x50, 0x60],
+            [0x70, 0x80, 0x90],
+            [0xA0, 0xB0, 0xC0],
+        ])
+    );
+}
+
+#[test]
+fn malformed_palettes_are_refused() {
+    let error = parse_palette("sepia").unwrap_err();
+    assert!(error.contains("mono, amber"), "{}", error);
+    assert!(parse_palette("#000000,#FFFFFF,#808080").is_err());
+    assert!(parse_palette("#000000,#FFFFFF,,").is_err());
+    assert_eq!(
+        parse_palette("#000000,#GGGGGG"),
+        Err("'#GGGGGG' is not a color, expected #RRGGBB".to_string())
+    );
+    assert!(parse_color("#FFF").is_err());
+    assert!(parse_color("#+FFFFF").is_err());
+}
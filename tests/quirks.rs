@@ -0,0 +1,186 @@
+use chip_8::cpu::schema::{Chip8Error, Instruction, Profile, START_ADRR};
+use chip_8::machine::schema::Machine;
+
+const PROFILES: [Profile; 5] = [
+    Profile::CosmacVip,
+    Profile::Chip48,
+    Profile::SuperChip,
+    Profile::XoChip,
+    Profile::Modern,
+];
+
+/// Exécute les `rom.len() / 2` instructions du programme avec les quirks du profil.
+fn run(profile: Profile, rom: &[u8]) -> Machine {
+    let mut machine = Machine::new(false, profile.quirks());
+    machine.cpu.set_mem_size(profile.mem_size());
+    machine.cpu.mem[START_ADRR..START_ADRR + rom.len()].copy_from_slice(rom);
+    for _ in 0..rom.len() / 2 {
+        machine.step().unwrap();
+    }
+    machine
+}
+
+#[test]
+fn shift_source_register() {
+    // V1 = 03, V2 = 81, 8126 (SHR V1, V2)
+    let rom = [0x61, 0x03, 0x62, 0x81, 0x81, 0x26];
+    for profile in PROFILES {
+        let machine = run(profile, &rom);
+        let expected = match profile {
+            Profile::CosmacVip | Profile::XoChip => 0x40,
+            _ => 0x01,
+        };
+        assert_eq!(machine.cpu.V[1], expected, "{:?}", profile);
+        assert_eq!(machine.cpu.V[0xF], 1, "{:?}", profile);
+    }
+}
+
+#[test]
+fn load_store_increments_i() {
+    // I = 300, V0-V2 -> [I], puis [I] -> V0-V2
+    let rom = [0xA3, 0x00, 0xF2, 0x55, 0xA3, 0x00, 0xF2, 0x65];
+    for profile in PROFILES {
+        let machine = run(profile, &rom);
+        let expected = match profile {
+            Profile::CosmacVip | Profile::XoChip => 0x303,
+            _ => 0x300,
+        };
+        assert_eq!(machine.cpu.I, expected, "{:?}", profile);
+    }
+}
+
+#[test]
+fn jump_offset_register() {
+    // V0 = 04, V2 = 08, B210
+    let rom = [0x60, 0x04, 0x62, 0x08, 0xB2, 0x10];
+    for profile in PROFILES {
+        let machine = run(profile, &rom);
+        let expected = match profile {
+            Profile::Chip48 | Profile::SuperChip => 0x218,
+            _ => 0x214,
+        };
+        assert_eq!(machine.cpu.pc, expected, "{:?}", profile);
+    }
+}
+
+#[test]
+fn logic_resets_vf() {
+    // VF = 05, V0 = 0C, V1 = 03, puis OR, AND et XOR
+    for op in [0x1, 0x2, 0x3] {
+        let rom = [0x6F, 0x05, 0x60, 0x0C, 0x61, 0x03, 0x80, 0x10 | op];
+        for profile in PROFILES {
+            let machine = run(profile, &rom);
+            let expected = match profile {
+                Profile::CosmacVip => 0,
+                _ => 5,
+            };
+            assert_eq!(machine.cpu.V[0xF], expected, "{:?} 801{:X}", profile, op);
+        }
+    }
+}
+
+#[test]
+fn sprites_clip_or_wrap_at_the_edge() {
+    // chiffre 0 de la police (rangée F0) dessiné en x = 62 : deux pixels débordent
+    let rom = [0x60, 62, 0x61, 0x00, 0xA0, 0x00, 0xD0, 0x11];
+    for profile in PROFILES {
+        let machine = run(profile, &rom);
+        assert_ne!(machine.screen.pixel[63][0], 0, "{:?}", profile);
+        let wrapped = machine.screen.pixel[0][0] != 0;
+        let clips = matches!(
+            profile,
+            Profile::CosmacVip | Profile::Chip48 | Profile::SuperChip
+        );
+        assert_eq!(wrapped, !clips, "{:?}", profile);
+    }
+}
+
+#[test]
+fn profiles_parse_their_aliases() {
+    assert_eq!("chip-8".parse::<Profile>(), Ok(Profile::CosmacVip));
+    assert_eq!("SCHIP".parse::<Profile>(), Ok(Profile::SuperChip));
+    assert_eq!("xo-chip".parse::<Profile>(), Ok(Profile::XoChip));
+    assert!("xo".parse::<Profile>().is_err());
+}
+
+#[test]
+fn xo_chip_addresses_64k_of_memory() {
+    assert_eq!(Profile::XoChip.mem_size(), 0x10000);
+    assert_eq!(Profile::SuperChip.mem_size(), 0x1000);
+}
+
+#[test]
+fn superchip_opcodes_are_unknown_to_older_interpreters() {
+    // 00FF (HIGH)
+    for profile in PROFILES {
+        let mut machine = Machine::new(false, profile.quirks());
+        machine.load_rom(&[0x00, 0xFF], START_ADRR).unwrap();
+        let result = machine.step();
+        if profile.supports(Instruction::Hires) {
+            assert_eq!(result, Ok(()), "{:?}", profile);
+            assert!(machine.screen.hires, "{:?}", profile);
+        } else {
+            assert_eq!(
+                result,
+                Err(Chip8Error::UnknownOpcode {
+                    pc: 0x200,
+                    opcode: 0x00FF
+                }),
+                "{:?}",
+                profile
+            );
+            assert!(!machine.screen.hires, "{:?}", profile);
+        }
+    }
+    assert!(!Profile::CosmacVip.supports(Instruction::Hires));
+    assert!(!Profile::Chip48.supports(Instruction::LdHf { x: 0 }));
+    assert!(Profile::SuperChip.supports(Instruction::Hires));
+}
+
+#[test]
+fn xochip_opcodes_are_unknown_outside_xochip_and_modern() {
+    // 5012 (SAVE V1-V2), F000 NNNN, F101 (PLANE 1), F002, F03A, 00D1
+    let opcodes: [&[u8]; 6] = [
+        &[0x51, 0x22],
+        &[0xF0, 0x00, 0x03, 0x00],
+        &[0xF1, 0x01],
+        &[0xF0, 0x02],
+        &[0xF0, 0x3A],
+        &[0x00, 0xD1],
+    ];
+    for profile in PROFILES {
+        let xochip = matches!(profile, Profile::XoChip | Profile::Modern);
+        for rom in opcodes {
+            let mut machine = Machine::new(false, profile.quirks());
+            machine.cpu.set_mem_size(profile.mem_size());
+            machine.load_rom(rom, START_ADRR).unwrap();
+            let result = machine.step();
+            assert_eq!(
+                result.is_ok(),
+                xochip,
+                "{:?} {:02X?}: {:?}",
+                profile,
+                rom,
+                result
+            );
+        }
+    }
+}
+
+#[test]
+fn skipping_a_long_load_needs_xochip() {
+    // V0 = 00, SE V0, 00 puis F000 0300 : 4 octets sautés seulement si F000 existe
+    let rom = [0x60, 0x00, 0x30, 0x00, 0xF0, 0x00, 0x03, 0x00];
+    for profile in PROFILES {
+        let mut machine = Machine::new(false, profile.quirks());
+        machine.load_rom(&rom, START_ADRR).unwrap();
+        machine.step().unwrap();
+        machine.step().unwrap();
+        let expected = if profile.supports(Instruction::LdILong) {
+            0x208
+        } else {
+            0x206
+        };
+        assert_eq!(machine.cpu.pc, expected, "{:?}", profile);
+    }
+}
@@ -0,0 +1,42 @@
+use chip_8::cpu::schema::{RngKind, START_ADRR};
+use chip_8::machine::schema::Machine;
+
+fn draws(kind: &RngKind, seed: u64, count: usize) -> Vec<u8> {
+    let mut source = kind.source(seed);
+    (0..count).map(|_| source.next_byte()).collect()
+}
+
+#[test]
+fn seeded_sources_repeat_their_draws() {
+    for kind in [RngKind::XorShift, RngKind::FontTable] {
+        assert_eq!(draws(&kind, 42, 32), draws(&kind, 42, 32), "{}", kind);
+        assert_ne!(draws(&kind, 42, 32), draws(&kind, 43, 32), "{}", kind);
+    }
+    // la graine 0 ne bloque pas xorshift sur zéro
+    assert_ne!(draws(&RngKind::XorShift, 0, 8), vec![0; 8]);
+}
+
+#[test]
+fn sequences_loop_and_go_through_the_mask() {
+    let kind: RngKind = "seq:0F,F0,0x3C".parse().unwrap();
+    assert_eq!(kind, RngKind::Sequence(vec![0x0F, 0xF0, 0x3C]));
+    assert_eq!(kind.to_string(), "seq:0F,F0,3C");
+    assert_eq!(draws(&kind, 0, 4), [0x0F, 0xF0, 0x3C, 0x0F]);
+
+    // C0FF puis C13C
+    let mut machine = Machine::new(false, Default::default());
+    machine
+        .load_rom(&[0xC0, 0xFF, 0xC1, 0x3C], START_ADRR)
+        .unwrap();
+    machine.cpu.rng = kind.source(0);
+    machine.step().unwrap();
+    machine.step().unwrap();
+    assert_eq!(machine.cpu.V[..2], [0x0F, 0x30]);
+}
+
+#[test]
+fn unknown_sources_are_refused() {
+    assert!("mersenne".parse::<RngKind>().is_err());
+    assert!("seq:0G".parse::<RngKind>().is_err());
+    assert_eq!(RngKind::Os.replayable(), RngKind::XorShift);
+}
@@ -0,0 +1,90 @@
+mod common;
+
+use chip_8::rewind::schema::Rewind;
+
+use common::{frame, game, GAME};
+
+#[test]
+fn stepping_back_restores_every_snapshot() {
+    let mut machine = game(GAME, 3);
+    let mut rewind = Rewind::new(1 << 20, 1);
+    let mut states = Vec::new();
+    for n in 0..120 {
+        frame(&mut machine, n);
+        rewind.record(&machine, 1);
+        states.push(machine.save_state());
+    }
+    assert_eq!(rewind.len_frames(), 119);
+
+    for expected in states.iter().rev().skip(1) {
+        assert!(rewind.step_back(&mut machine));
+        assert!(machine.save_state() == *expected);
+    }
+    assert!(!rewind.step_back(&mut machine));
+    assert!(machine.save_state() == states[0]);
+}
+
+#[test]
+fn play_resumes_deterministically_after_a_rewind() {
+    let mut machine = game(GAME, 3);
+    let mut rewind = Rewind::new(1 << 20, 1);
+    for n in 0..60 {
+        frame(&mut machine, n);
+        rewind.record(&machine, 1);
+    }
+    let mut later = Vec::new();
+    for n in 60..90 {
+        frame(&mut machine, n);
+        rewind.record(&machine, 1);
+        later.push(machine.save_state());
+    }
+
+    // retour à la frame 60, le générateur de CXNN compris, puis mêmes entrées
+    for _ in 0..30 {
+        rewind.step_back(&mut machine);
+    }
+    for (n, expected) in (60..90).zip(&later) {
+        frame(&mut machine, n);
+        assert!(machine.save_state() == *expected, "frame {}", n);
+    }
+}
+
+#[test]
+fn interval_and_budget_bound_the_history() {
+    let mut machine = game(GAME, 3);
+    let mut rewind = Rewind::new(1 << 20, 5);
+    for n in 0..100 {
+        frame(&mut machine, n);
+        rewind.record(&machine, 1);
+    }
+    assert_eq!(rewind.len_frames(), 95);
+
+    // le budget couvre à peine l'instantané complet : l'historique reste court
+    let size = machine.save_state().len();
+    let mut rewind = Rewind::new(size + 64, 1);
+    for n in 0..200 {
+        frame(&mut machine, n);
+        rewind.record(&machine, 1);
+    }
+    assert!(rewind.len_frames() < 64);
+    assert!(rewind.used <= 64);
+}
+
+#[test]
+fn rejected_snapshot_drops_the_history() {
+    let mut machine = game(GAME, 3);
+    let mut rewind = Rewind::new(1 << 20, 1);
+    for n in 0..10 {
+        frame(&mut machine, n);
+        rewind.record(&machine, 1);
+    }
+
+    // une autre ROM a été chargée entre-temps : les instantanés ne s'appliquent plus
+    machine.rom_hash ^= 1;
+    let before = machine.save_state();
+    assert!(!rewind.step_back(&mut machine));
+    assert!(machine.save_state() == before);
+    assert_eq!(rewind.len_frames(), 0);
+    assert!(rewind.latest.is_empty());
+    assert!(!rewind.step_back(&mut machine));
+}
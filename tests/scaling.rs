@@ -0,0 +1,61 @@
+use chip_8::display::schema::{Scaling, Viewport};
+
+fn viewport(scaling: Scaling, window: (u32, u32)) -> Viewport {
+    scaling.viewport(window, (64, 32))
+}
+
+#[test]
+fn integer_scaling_keeps_square_pixels() {
+    let view = viewport(Scaling::Integer, (1000, 600));
+    assert_eq!(
+        (view.x, view.y, view.width, view.height),
+        (20, 60, 960, 480)
+    );
+    // haute résolution dans la même fenêtre : 7 pixels par case
+    let view = Scaling::Integer.viewport((1000, 600), (128, 64));
+    assert_eq!(
+        (view.x, view.y, view.width, view.height),
+        (52, 76, 896, 448)
+    );
+}
+
+#[test]
+fn aspect_scaling_fills_one_dimension() {
+    let wide = viewport(Scaling::Aspect, (1920, 600));
+    assert_eq!(
+        (wide.x, wide.y, wide.width, wide.height),
+        (360, 0, 1200, 600)
+    );
+    let tall = viewport(Scaling::Aspect, (1000, 600));
+    assert_eq!(
+        (tall.x, tall.y, tall.width, tall.height),
+        (0, 50, 1000, 500)
+    );
+}
+
+#[test]
+fn windows_smaller_than_the_screen_fall_back_to_aspect() {
+    let view = viewport(Scaling::Integer, (50, 20));
+    assert_eq!(view, viewport(Scaling::Aspect, (50, 20)));
+    assert_eq!((view.x, view.y, view.width, view.height), (5, 0, 40, 20));
+}
+
+#[test]
+fn cells_tile_the_viewport_without_gaps() {
+    let view = viewport(Scaling::Aspect, (1000, 600));
+    let mut right = view.x as i32;
+    for col in 0..view.cols {
+        let (x, _, w, _) = view.cell(col, 0);
+        assert_eq!(x, right, "column {}", col);
+        assert!(w == 15 || w == 16, "column {} is {} wide", col, w);
+        right = x + w as i32;
+    }
+    assert_eq!(right as u32, view.x + view.width);
+    assert_eq!(view.cell(0, 31), (0, 50 + 500 - 16, 15, 16));
+}
+
+#[test]
+fn scaling_names_parse() {
+    assert_eq!("Aspect".parse(), Ok(Scaling::Aspect));
+    assert!("stretch".parse::<Scaling>().is_err());
+}
@@ -0,0 +1,69 @@
+use std::time::{Duration, Instant};
+
+use chip_8::scheduler::schema::{Scheduler, Slice, MAX_CATCH_UP, TIMER_HZ};
+
+/// 1/64 s : représentable exactement, les budgets ne dérivent pas.
+const STEP: Duration = Duration::from_micros(15_625);
+
+/// Fait avancer l'horloge de `steps` pas de `STEP` et cumule le travail dû.
+fn run(scheduler: &mut Scheduler, start: Instant, steps: u32) -> Slice {
+    let mut total = Slice::default();
+    for i in 1..=steps {
+        let slice = scheduler.advance(start + STEP * i);
+        total.instructions += slice.instructions;
+        total.timer_ticks += slice.timer_ticks;
+    }
+    total
+}
+
+#[test]
+fn timers_tick_at_60_hz() {
+    let start = Instant::now();
+    let mut scheduler = Scheduler::new(700, start);
+    let total = run(&mut scheduler, start, 64 * 3);
+    assert_eq!(total.timer_ticks, 3 * TIMER_HZ);
+}
+
+#[test]
+fn instructions_follow_ips() {
+    let start = Instant::now();
+    for ips in [500, 640, 1000] {
+        let mut scheduler = Scheduler::new(ips, start);
+        let total = run(&mut scheduler, start, 64);
+        assert_eq!(total.instructions, ips, "ips {}", ips);
+    }
+}
+
+#[test]
+fn fractions_are_carried_to_the_next_slice() {
+    let start = Instant::now();
+    let mut scheduler = Scheduler::new(640, start);
+    // 1/64 s à 60 Hz : 0,9375 tick, pas encore de tick
+    assert_eq!(scheduler.advance(start + STEP).timer_ticks, 0);
+    assert!(scheduler.until_next_tick() < STEP);
+    assert_eq!(scheduler.advance(start + STEP * 2).timer_ticks, 1);
+}
+
+#[test]
+fn long_stalls_are_dropped() {
+    let start = Instant::now();
+    let mut scheduler = Scheduler::new(1000, start);
+    let slice = scheduler.advance(start + Duration::from_secs(5));
+    assert_eq!(slice.instructions, 100);
+    assert_eq!(slice.timer_ticks, 6);
+    assert_eq!(MAX_CATCH_UP, Duration::from_millis(100));
+
+    // l'horloge repart du moment du blocage, le retard n'est pas rattrapé ensuite
+    let slice = scheduler.advance(start + Duration::from_secs(5) + STEP);
+    assert_eq!(slice.instructions, 15);
+}
+
+#[test]
+fn a_clock_going_backwards_does_no_work() {
+    let start = Instant::now() + Duration::from_secs(1);
+    let mut scheduler = Scheduler::new(1000, start);
+    assert_eq!(
+        scheduler.advance(start - Duration::from_millis(10)),
+        Slice::default()
+    );
+}
@@ -0,0 +1,132 @@
+mod common;
+
+use std::process::{Command, Stdio};
+
+use chip_8::state::schema::{StateError, STATE_VERSION};
+use chip_8::state::state::slot_path;
+
+use common::{game, play, GAME, SPEED};
+
+#[test]
+fn loading_a_state_replays_the_same_frames() {
+    let mut machine = game(GAME, 7);
+    let state = machine.save_state();
+    play(&mut machine, 300);
+    let expected = machine.save_state();
+    assert_ne!(expected, state);
+
+    // le générateur de CXNN fait partie de l'état : une autre graine ne change rien
+    let mut other = game(GAME, 99);
+    play(&mut other, 25);
+    other.load_state(&state).unwrap();
+    assert_eq!(other.save_state(), state);
+    play(&mut other, 300);
+    assert_eq!(other.save_state(), expected);
+    assert_eq!(other.screen.to_text(), machine.screen.to_text());
+}
+
+#[test]
+fn state_from_another_rom_is_refused() {
+    let state = game(GAME, 1).save_state();
+    let mut machine = game("PONG", 1);
+    let before = machine.save_state();
+    assert!(matches!(
+        machine.load_state(&state),
+        Err(StateError::RomMismatch { .. })
+    ));
+    assert_eq!(machine.save_state(), before);
+}
+
+#[test]
+fn corrupt_states_are_refused() {
+    let mut machine = game(GAME, 1);
+    let state = machine.save_state();
+
+    let mut magic = state.clone();
+    magic[0] = b'X';
+    assert!(matches!(
+        machine.load_state(&magic),
+        Err(StateError::BadMagic)
+    ));
+
+    let mut version = state.clone();
+    version[4..6].copy_from_slice(&(STATE_VERSION + 1).to_le_bytes());
+    assert!(matches!(
+        machine.load_state(&version),
+        Err(StateError::UnsupportedVersion(_))
+    ));
+
+    assert!(matches!(
+        machine.load_state(&state[..state.len() - 1]),
+        Err(StateError::Truncated)
+    ));
+
+    // dernier pixel de l'écran, juste avant la touche attendue
+    let mut pixel = state.clone();
+    let last = pixel.len() - 2;
+    pixel[last] = 4;
+    assert!(matches!(
+        machine.load_state(&pixel),
+        Err(StateError::Invalid(_))
+    ));
+
+    let mut key = state.clone();
+    let last = key.len() - 1;
+    key[last] = 0x10;
+    assert!(matches!(
+        machine.load_state(&key),
+        Err(StateError::Invalid(_))
+    ));
+
+    assert_eq!(machine.save_state(), state);
+}
+
+#[test]
+fn slot_files_sit_next_to_the_rom() {
+    assert_eq!(
+        slot_path("games/BRIX.ch8", 2).unwrap().to_str(),
+        Some("games/BRIX.2.c8s")
+    );
+    assert_eq!(slot_path("-", 1), None);
+}
+
+/// `--load-state` rend aussi le générateur de CXNN : une graine passée en ligne de
+/// commande ne doit pas le remplacer.
+#[test]
+fn load_state_flag_keeps_the_saved_generator() {
+    let state = game(GAME, 7).save_state();
+    let dir = std::env::temp_dir();
+    let path = dir.join(format!("chip8-cli-{}.c8s", std::process::id()));
+    let dump = dir.join(format!("chip8-cli-{}.txt", std::process::id()));
+    std::fs::write(&path, &state).unwrap();
+
+    let mut expected = game(GAME, 99);
+    expected.load_state(&state).unwrap();
+    expected.keyboard.keys = [false; 16];
+    for _ in 0..300 {
+        expected.run_frame(SPEED).unwrap();
+    }
+
+    let status = Command::new(env!("CARGO_BIN_EXE_chip-8"))
+        .args([
+            "games/BRIX.ch8",
+            "--headless",
+            "--frames",
+            "300",
+            "--seed",
+            "99",
+        ])
+        .arg("--load-state")
+        .arg(&path)
+        .arg("--dump")
+        .arg(&dump)
+        .stdout(Stdio::null())
+        .status()
+        .unwrap();
+    let screen = std::fs::read_to_string(&dump).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    std::fs::remove_file(&dump).unwrap();
+
+    assert!(status.success());
+    assert_eq!(screen, expected.screen.to_text());
+}
@@ -0,0 +1,101 @@
+use std::fs::{self, File};
+use std::path::PathBuf;
+
+use chip_8::display::schema::{FrameBuffer, DEFAULT_PALETTE, HIRES_H, HIRES_W, WHITE};
+use chip_8::video::schema::{FrameSink, GifRecorder, RawRecorder};
+use chip_8::video::video::frame_size;
+
+fn temp(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("chip8-{}-{}", std::process::id(), name))
+}
+
+/// Écran basse résolution dont seul le pixel `(x, 0)` est allumé.
+fn frame(x: usize) -> Vec<u8> {
+    let mut screen = FrameBuffer::new();
+    screen.pixel[x][0] = WHITE;
+    screen.to_indexed(1)
+}
+
+/// Délais (en centièmes) et premier pixel allumé de chaque image du GIF.
+fn decode(path: &PathBuf) -> Vec<(u16, usize)> {
+    let mut options = gif::DecodeOptions::new();
+    options.set_color_output(gif::ColorOutput::Indexed);
+    let mut decoder = options.read_info(File::open(path).unwrap()).unwrap();
+    let mut frames = Vec::new();
+    while let Some(frame) = decoder.read_next_frame().unwrap() {
+        let lit = frame.buffer.iter().position(|&v| v != 0).unwrap();
+        frames.push((frame.delay, lit));
+    }
+    frames
+}
+
+#[test]
+fn low_resolution_pixels_cover_two_by_two_cells() {
+    assert_eq!(frame_size(3), (HIRES_W * 3, HIRES_H * 3));
+    let pixels = frame(1);
+    let width = HIRES_W as usize;
+    assert_eq!(pixels.len(), width * HIRES_H as usize);
+    assert_eq!(&pixels[..4], [0, 0, 1, 1]);
+    assert_eq!(&pixels[width..width + 4], [0, 0, 1, 1]);
+    assert_eq!(pixels[2 * width + 2], 0);
+}
+
+#[test]
+fn gif_merges_identical_frames_and_folds_short_ones() {
+    let path = temp("merge.gif");
+    let mut recorder = GifRecorder::create(&path, &DEFAULT_PALETTE, 1).unwrap();
+    // 1/60 s ne fait qu'un centième : la frame est abandonnée au profit de la suivante
+    recorder.push(&frame(0), 1).unwrap();
+    recorder.push(&frame(1), 1).unwrap();
+    recorder.push(&frame(1), 10).unwrap();
+    recorder.push(&frame(2), 6).unwrap();
+    recorder.finish().unwrap();
+
+    let frames = decode(&path);
+    fs::remove_file(&path).unwrap();
+    // 12 ticks = 20 centièmes, puis 18 ticks au total = 30 centièmes
+    assert_eq!(frames, [(20, 2), (10, 4)]);
+}
+
+#[test]
+fn gif_delays_do_not_drift_from_the_60_hz_clock() {
+    let path = temp("drift.gif");
+    let mut recorder = GifRecorder::create(&path, &DEFAULT_PALETTE, 1).unwrap();
+    for n in 0..60 {
+        recorder.push(&frame(n % 2), 2).unwrap();
+    }
+    recorder.finish().unwrap();
+
+    let frames = decode(&path);
+    fs::remove_file(&path).unwrap();
+    // 2 ticks valent 3,33 centièmes, arrondis à 3 ou 4 selon l'instant
+    assert_eq!(frames.len(), 60);
+    assert!(frames.iter().all(|&(delay, _)| delay == 3 || delay == 4));
+    let total: u32 = frames.iter().map(|&(delay, _)| delay as u32).sum();
+    assert_eq!(total, 200);
+}
+
+#[test]
+fn raw_video_writes_one_frame_per_tick() {
+    let path = temp("frames.rgb");
+    let mut recorder = RawRecorder::create(&path, &DEFAULT_PALETTE).unwrap();
+    recorder.push(&frame(0), 3).unwrap();
+    recorder.finish().unwrap();
+
+    let data = fs::read(&path).unwrap();
+    fs::remove_file(&path).unwrap();
+    let size = (HIRES_W * HIRES_H * 3) as usize;
+    assert_eq!(data.len(), 3 * size);
+    assert_eq!(data[..3], DEFAULT_PALETTE[1]);
+    assert_eq!(data[size..size + 3], DEFAULT_PALETTE[1]);
+}
+
+#[test]
+fn oversized_gif_is_an_error() {
+    let path = temp("large.gif");
+    let error = GifRecorder::create(&path, &DEFAULT_PALETTE, 1024)
+        .err()
+        .unwrap();
+    assert_eq!(error.kind(), std::io::ErrorKind::InvalidInput);
+    assert!(!path.exists());
+}